@@ -1,7 +1,6 @@
-use std::{collections::VecDeque, str::FromStr};
+use std::str::FromStr;
 
-use anyhow::Context;
-use fxhash::FxHashMap;
+use common::netlist::{Logic, Netlist, Operand};
 use nom::{
     Finish, IResult, Parser,
     branch::alt,
@@ -18,74 +17,23 @@ pub fn run(input: &str, _: bool) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> u16 {
-    let wires: FxHashMap<_, _> = input
-        .lines()
-        .flat_map(Wire::from_str)
-        .map(|w| (w.name.clone(), w))
-        .collect();
-    let res = eval(&wires).unwrap();
-    *res.get("a").unwrap()
+    let mut circuit = circuit(input);
+    circuit.get("a").unwrap() as u16
 }
 
 pub fn part02(input: &str) -> u16 {
-    let wire_a = part01(input);
+    let mut circuit = circuit(input);
+    let wire_a = circuit.get("a").unwrap();
+    circuit.set("b", wire_a);
+    circuit.get("a").unwrap() as u16
+}
 
-    let mut wires: FxHashMap<_, _> = input
+fn circuit(input: &str) -> Netlist<Logic<16>> {
+    input
         .lines()
         .flat_map(Wire::from_str)
-        .map(|w| (w.name.clone(), w))
-        .collect();
-
-    wires
-        .entry("b".to_string())
-        .and_modify(|w| w.input = Input::Signal(Expr::Number(wire_a)));
-
-    let res = eval(&wires).unwrap();
-    *res.get("a").unwrap()
-}
-
-// evaluate the graph topologically
-fn eval(wires: &FxHashMap<String, Wire>) -> anyhow::Result<FxHashMap<&str, u16>> {
-    let mut queue = VecDeque::new();
-    let mut in_degree = FxHashMap::<&str, u32>::default();
-    let mut in_edges = FxHashMap::<&str, Vec<&str>>::default();
-    for (_, wire) in wires.iter() {
-        let depends_on = wire.depends_on();
-
-        if depends_on.is_empty() {
-            queue.push_back(wire.name.as_str());
-            continue;
-        }
-
-        for dep in depends_on {
-            in_edges.entry(dep).or_default().push(&wire.name);
-            in_degree
-                .entry(wire.name.as_str())
-                .and_modify(|n| *n += 1)
-                .or_insert(1);
-        }
-    }
-
-    let mut scope = FxHashMap::default();
-    while let Some(name) = queue.pop_front() {
-        let wire = wires
-            .get(name)
-            .with_context(|| format!("wire '{}' not found", name))?;
-
-        let value = wire.signal(&scope);
-        scope.insert(name, value);
-
-        if let Some(in_edges) = in_edges.get(name) {
-            in_edges.iter().for_each(|v| {
-                in_degree.entry(v).and_modify(|n| *n -= 1);
-                if in_degree[v] == 0 {
-                    queue.push_back(v);
-                }
-            });
-        }
-    }
-
-    Ok(scope)
+        .map(Wire::into_gate)
+        .collect()
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -115,57 +63,28 @@ enum Expr {
     Var(String),
 }
 
-impl Expr {
-    fn eval(&self, scope: &FxHashMap<&str, u16>) -> u16 {
-        match self {
-            Expr::Number(n) => *n,
-            Expr::Var(name) => *scope.get(name.as_str()).unwrap(),
+impl From<Expr> for Operand {
+    fn from(expr: Expr) -> Self {
+        match expr {
+            Expr::Number(n) => Operand::Value(n as u64),
+            Expr::Var(name) => Operand::Wire(name),
         }
     }
 }
 
 impl Wire {
-    fn signal(&self, scope: &FxHashMap<&str, u16>) -> u16 {
-        match &self.input {
-            Input::Signal(o) => o.eval(scope),
+    fn into_gate(self) -> (String, Logic<16>) {
+        let gate = match self.input {
+            Input::Signal(e) => Logic::Buf(e.into()),
             Input::Op(op) => match op {
-                Op::And(l, r) => l.eval(scope) & r.eval(scope),
-                Op::Or(l, r) => l.eval(scope) | r.eval(scope),
-                Op::Not(i) => !*scope.get(i.as_str()).unwrap(),
-                Op::LShift(i, n) => scope.get(i.as_str()).unwrap() << n,
-                Op::RShift(i, n) => scope.get(i.as_str()).unwrap() >> n,
+                Op::And(l, r) => Logic::And(l.into(), r.into()),
+                Op::Or(l, r) => Logic::Or(l.into(), r.into()),
+                Op::Not(i) => Logic::Not(Operand::Wire(i)),
+                Op::LShift(i, n) => Logic::LShift(Operand::Wire(i), n),
+                Op::RShift(i, n) => Logic::RShift(Operand::Wire(i), n),
             },
-        }
-    }
-
-    fn depends_on(&self) -> Vec<&str> {
-        let mut deps = vec![];
-
-        macro_rules! maybe_add_expr {
-            ( $( $e:ident),* ) => {
-                $(
-                    if let Expr::Var(name) = $e {
-                        deps.push(name.as_str());
-                    }
-                )*
-            };
-        }
-
-        match &self.input {
-            Input::Signal(o) => maybe_add_expr!(o),
-            Input::Op(op) => match op {
-                Op::And(lhs, rhs) => {
-                    maybe_add_expr!(lhs, rhs);
-                }
-                Op::Or(lhs, rhs) => {
-                    maybe_add_expr!(lhs, rhs);
-                }
-                Op::Not(o) => deps.push(o.as_str()),
-                Op::LShift(o, _) => deps.push(o.as_str()),
-                Op::RShift(o, _) => deps.push(o.as_str()),
-            },
-        }
-        deps
+        };
+        (self.name, gate)
     }
 }
 
@@ -224,6 +143,7 @@ fn parse_infix(input: &str) -> IResult<&str, Op> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Context;
     use rstest::rstest;
 
     #[rstest]
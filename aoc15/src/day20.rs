@@ -1,4 +1,4 @@
-use num::integer::sqrt;
+use common::numtheory::for_each_divisor;
 
 pub fn run(input: &str, _: bool) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -12,7 +12,7 @@ pub fn part01(input: &str) -> usize {
 
     for i in 1.. {
         let mut delivered = 0;
-        do_factors(i, |f| delivered += f);
+        for_each_divisor(i, |f| delivered += f);
         delivered *= 10;
         if delivered >= input {
             return i;
@@ -27,7 +27,7 @@ pub fn part02(input: &str) -> usize {
 
     for i in 1.. {
         let mut delivered = 0;
-        do_factors(i, |f| {
+        for_each_divisor(i, |f| {
             if f * 50 >= i {
                 delivered += f
             }
@@ -41,21 +41,6 @@ pub fn part02(input: &str) -> usize {
     panic!("no solution found")
 }
 
-fn do_factors<F: FnMut(usize)>(x: usize, mut yld: F) {
-    yld(1);
-    yld(x);
-    let root = sqrt(x);
-    for n in 2..=root {
-        if x.is_multiple_of(n) {
-            yld(n);
-            let comp = x / n;
-            if comp != n {
-                yld(comp);
-            }
-        }
-    }
-}
-
 fn parse_input(input: &str) -> usize {
     input.lines().next().unwrap().parse().unwrap()
 }
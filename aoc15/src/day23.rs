@@ -0,0 +1,85 @@
+use anyhow::Context;
+use common::asm::{Flow, Instruction, Machine};
+
+pub fn run(input: &str, _: bool) -> anyhow::Result<crate::SolveInfo> {
+    Ok(crate::SolveInfo {
+        part01: part01(input)?.to_string(),
+        part02: part02(input)?.to_string(),
+    })
+}
+
+pub fn part01(input: &str) -> anyhow::Result<u64> {
+    Ok(execute(input, [0, 0])?[1])
+}
+
+pub fn part02(input: &str) -> anyhow::Result<u64> {
+    Ok(execute(input, [1, 0])?[1])
+}
+
+// runs the program and returns the registers a and b at the end
+fn execute(input: &str, registers: [u64; 2]) -> anyhow::Result<[u64; 2]> {
+    let mut computer = Machine::<Op>::parse(input, registers)?;
+    computer.run();
+    Ok(computer.state)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Hlf(usize),
+    Tpl(usize),
+    Inc(usize),
+    Jmp(isize),
+    Jie(usize, isize),
+    Jio(usize, isize),
+}
+
+impl Instruction for Op {
+    type State = [u64; 2];
+
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        let (op, args) = line.split_once(' ').context("no arguments")?;
+        let mut args = args.split(", ");
+        let mut arg = || args.next().context("missing argument");
+        let register = |r: &str| match r {
+            "a" => Ok(0),
+            "b" => Ok(1),
+            _ => anyhow::bail!("unknown register {r}"),
+        };
+        Ok(match op {
+            "hlf" => Op::Hlf(register(arg()?)?),
+            "tpl" => Op::Tpl(register(arg()?)?),
+            "inc" => Op::Inc(register(arg()?)?),
+            "jmp" => Op::Jmp(arg()?.parse()?),
+            "jie" => Op::Jie(register(arg()?)?, arg()?.parse()?),
+            "jio" => Op::Jio(register(arg()?)?, arg()?.parse()?),
+            _ => anyhow::bail!("unknown instruction {op}"),
+        })
+    }
+
+    fn execute(&self, registers: &mut [u64; 2]) -> Flow {
+        match *self {
+            Op::Hlf(r) => registers[r] /= 2,
+            Op::Tpl(r) => registers[r] *= 3,
+            Op::Inc(r) => registers[r] += 1,
+            Op::Jmp(offset) => return Flow::Jump(offset),
+            Op::Jie(r, offset) if registers[r].is_multiple_of(2) => return Flow::Jump(offset),
+            // "jump if one", not odd
+            Op::Jio(r, offset) if registers[r] == 1 => return Flow::Jump(offset),
+            Op::Jie(..) | Op::Jio(..) => (),
+        }
+        Flow::Next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "inc a\njio a, +2\ntpl a\ninc a";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!([2, 0], execute(SAMPLE, [0, 0]).unwrap());
+        assert_eq!([7, 0], execute(SAMPLE, [1, 0]).unwrap());
+    }
+}
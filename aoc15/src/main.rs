@@ -15,32 +15,32 @@ struct Cli {
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let days = [
-        day01::run,
-        day02::run,
-        day03::run,
-        day04::run,
-        day05::run,
-        day06::run,
-        day07::run,
-        day08::run,
-        day09::run,
-        day10::run,
-        day11::run,
-        day12::run,
-        day13::run,
-        day14::run,
-        day15::run,
-        day16::run,
-        day17::run,
-        day18::run,
-        day19::run,
-        day20::run,
-        day21::run,
-        // day22::run,
-        // day23::run,
-        // day24::run,
-        // day25::run,
+    let days: [Option<Solver>; 23] = [
+        Some(day01::run),
+        Some(day02::run),
+        Some(day03::run),
+        Some(day04::run),
+        Some(day05::run),
+        Some(day06::run),
+        Some(day07::run),
+        Some(day08::run),
+        Some(day09::run),
+        Some(day10::run),
+        Some(day11::run),
+        Some(day12::run),
+        Some(day13::run),
+        Some(day14::run),
+        Some(day15::run),
+        Some(day16::run),
+        Some(day17::run),
+        Some(day18::run),
+        Some(day19::run),
+        Some(day20::run),
+        Some(day21::run),
+        None, // day22 isn't solved yet
+        Some(day23::run),
+        // Some(day24::run),
+        // Some(day25::run),
     ];
 
     if let Some(day) = cli.day {
@@ -48,13 +48,14 @@ fn main() -> anyhow::Result<()> {
             anyhow::bail!("Day {} not yet solved!", day)
         }
 
+        let f = days[day - 1].with_context(|| format!("Day {} not yet solved!", day))?;
         let start = Instant::now();
         let input = day_input(day, cli.sample)?;
-        let f = days[day - 1];
         let solve = f(&input, cli.sample)?;
         print_solve(day, &solve, start.elapsed());
     } else {
         for (day, f) in days.iter().enumerate() {
+            let Some(f) = f else { continue };
             let start = Instant::now();
             let day = day + 1;
             let input = day_input(day, cli.sample)?;
@@ -67,6 +68,8 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+type Solver = fn(&str, bool) -> anyhow::Result<SolveInfo>;
+
 fn day_input(day: usize, sample: bool) -> anyhow::Result<String> {
     let fname = if sample {
         format!("aoc15/inputs/day{:02}.sample.txt", day)
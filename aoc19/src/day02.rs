@@ -1,5 +1,7 @@
 use itertools::iproduct;
 
+use crate::intcode::{Intcode, Word};
+
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
         part01: part01(input)?.to_string(),
@@ -7,55 +9,27 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     })
 }
 
-pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let mut memory = parse_input(input);
-    memory[1] = 12;
-    memory[2] = 2;
-
-    Ok(eval_intcode(&mut memory))
-}
-
-pub fn part02(input: &str) -> anyhow::Result<usize> {
-    const EXPECT: usize = 19690720;
-
-    let memory = parse_input(input);
-
-    Ok(iproduct!(0..100, 0..100)
-        .find(|&(noun, verb)| {
-            let mut memory = memory.clone();
-            memory[1] = noun;
-            memory[2] = verb;
-
-            eval_intcode(&mut memory) == EXPECT
-        })
-        .map(|(noun, verb)| 100 * noun + verb)
-        .unwrap())
+pub fn part01(input: &str) -> anyhow::Result<Word> {
+    eval(input.parse()?, 12, 2)
 }
 
-fn eval_intcode(memory: &mut [usize]) -> usize {
-    let mut ip = 0;
-    while memory[ip] != 99 {
-        let lptr = memory[ip + 1];
-        let rptr = memory[ip + 2];
-        let optr = memory[ip + 3];
+pub fn part02(input: &str) -> anyhow::Result<Word> {
+    const EXPECT: Word = 19690720;
 
-        match memory[ip] {
-            1 => memory[optr] = memory[lptr] + memory[rptr],
-            2 => memory[optr] = memory[lptr] * memory[rptr],
-            op => unreachable!("invalid op code {op}"),
+    let program: Intcode = input.parse()?;
+    for (noun, verb) in iproduct!(0..100, 0..100) {
+        if eval(program.clone(), noun, verb)? == EXPECT {
+            return Ok(100 * noun + verb);
         }
-        ip += 4;
     }
-
-    memory[0]
+    anyhow::bail!("no noun and verb give {EXPECT}")
 }
 
-fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .trim()
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect()
+fn eval(mut program: Intcode, noun: Word, verb: Word) -> anyhow::Result<Word> {
+    program.write(1, noun);
+    program.write(2, verb);
+    program.run([])?;
+    Ok(program.read(0))
 }
 
 #[cfg(test)]
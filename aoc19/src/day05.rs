@@ -0,0 +1,48 @@
+use crate::intcode::{Intcode, Word};
+
+pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
+    Ok(crate::SolveInfo {
+        part01: part01(input)?.to_string(),
+        part02: part02(input)?.to_string(),
+    })
+}
+
+pub fn part01(input: &str) -> anyhow::Result<Word> {
+    // test the air conditioner unit
+    diagnostic_code(input, 1)
+}
+
+pub fn part02(input: &str) -> anyhow::Result<Word> {
+    // test the thermal radiator controller
+    diagnostic_code(input, 5)
+}
+
+// every output before the last is a test result that should be 0
+fn diagnostic_code(input: &str, system: Word) -> anyhow::Result<Word> {
+    let mut program: Intcode = input.parse()?;
+    let output = program.run([system])?;
+    let Some((code, tests)) = output.split_last() else {
+        anyhow::bail!("no diagnostic code");
+    };
+    anyhow::ensure!(tests.iter().all(|t| *t == 0), "tests failed: {tests:?}");
+    Ok(*code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // outputs 999 if the input is below 8, 1000 if it is 8 and 1001 if it is above 8
+    const SAMPLE: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,\
+                          1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,\
+                          1105,1,46,98,99";
+
+    #[rstest]
+    #[case(7, 999)]
+    #[case(8, 1000)]
+    #[case(9, 1001)]
+    fn test_sample(#[case] system: Word, #[case] expected: Word) {
+        assert_eq!(expected, diagnostic_code(SAMPLE, system).unwrap());
+    }
+}
@@ -0,0 +1,125 @@
+// Turns Intcode back into something readable. Position parameters are shown as `[addr]`, relative
+// ones as `[rb+offset]` and immediates as plain numbers, and every instruction gets a comment
+// saying what it does.
+
+use std::fmt::Write;
+
+use crate::intcode::{Mode, Opcode, Word, decode};
+
+/// The whole program, one instruction per line. Anything that doesn't decode as an instruction
+/// (usually the data after the code) is shown as `data`.
+pub fn disassemble(program: &[Word]) -> String {
+    let mut out = String::new();
+    let mut addr = 0;
+    while addr < program.len() {
+        let (text, len) = instruction(program, addr).unwrap_or_else(|| data(program[addr]));
+        writeln!(out, "{addr:>5}: {text}").unwrap();
+        addr += len;
+    }
+    out
+}
+
+/// The instruction at `addr` and how many words it takes up, or `None` if there isn't a valid
+/// instruction there.
+pub fn instruction(program: &[Word], addr: usize) -> Option<(String, usize)> {
+    let (op, modes) = decode(*program.get(addr)?).ok()?;
+    let params = program.get(addr + 1..addr + 1 + op.arity())?;
+    if op.writes().is_some_and(|n| modes[n] == Mode::Immediate) {
+        return None;
+    }
+    let p: Vec<String> = params
+        .iter()
+        .zip(modes)
+        .map(|(&value, mode)| operand(value, mode))
+        .collect();
+
+    let comment = match op {
+        Opcode::Add => format!("{} = {} + {}", p[2], p[0], p[1]),
+        Opcode::Mul => format!("{} = {} * {}", p[2], p[0], p[1]),
+        Opcode::Input => format!("{} = input", p[0]),
+        Opcode::Output => format!("output {}", p[0]),
+        Opcode::JumpIfTrue => format!("if {} != 0 goto {}", p[0], p[1]),
+        Opcode::JumpIfFalse => format!("if {} == 0 goto {}", p[0], p[1]),
+        Opcode::LessThan => format!("{} = {} < {}", p[2], p[0], p[1]),
+        Opcode::Equals => format!("{} = {} == {}", p[2], p[0], p[1]),
+        Opcode::AdjustBase => format!("rb += {}", p[0]),
+        Opcode::Halt => String::new(),
+    };
+    let text = format!("{:<4} {}", mnemonic(op), p.join(", "));
+    let text = if comment.is_empty() {
+        text.trim_end().to_string()
+    } else {
+        format!("{text:<32} ; {comment}")
+    };
+    Some((text, 1 + op.arity()))
+}
+
+fn data(value: Word) -> (String, usize) {
+    (format!("data {value}"), 1)
+}
+
+fn mnemonic(op: Opcode) -> &'static str {
+    match op {
+        Opcode::Add => "add",
+        Opcode::Mul => "mul",
+        Opcode::Input => "in",
+        Opcode::Output => "out",
+        Opcode::JumpIfTrue => "jt",
+        Opcode::JumpIfFalse => "jf",
+        Opcode::LessThan => "lt",
+        Opcode::Equals => "eq",
+        Opcode::AdjustBase => "arb",
+        Opcode::Halt => "halt",
+    }
+}
+
+fn operand(value: Word, mode: Mode) -> String {
+    match mode {
+        Mode::Position => format!("[{value}]"),
+        Mode::Immediate => value.to_string(),
+        Mode::Relative if value < 0 => format!("[rb{value}]"),
+        Mode::Relative => format!("[rb+{value}]"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        let program = [
+            3, 9, 1008, 9, 8, 10, 204, -1, 99, 0, 0, 1107, 1, 2, 3, 11101, 1,
+        ];
+        let expected = "    0: in   [9]                         ; [9] = input
+    2: eq   [9], 8, [10]                ; [10] = [9] == 8
+    6: out  [rb-1]                      ; output [rb-1]
+    8: halt
+    9: data 0
+   10: data 0
+   11: lt   1, 2, [3]                   ; [3] = 1 < 2
+   15: data 11101
+   16: data 1
+";
+        assert_eq!(expected, disassemble(&program));
+    }
+
+    #[test]
+    fn test_instruction() {
+        let program = [1105, 1, 7, 109, 19];
+        assert_eq!(
+            Some((
+                "jt   1, 7                        ; if 1 != 0 goto 7".to_string(),
+                3
+            )),
+            instruction(&program, 0)
+        );
+        assert_eq!(
+            Some(("arb  19                          ; rb += 19".to_string(), 2)),
+            instruction(&program, 3)
+        );
+        // runs off the end
+        assert_eq!(None, instruction(&program, 2));
+        assert_eq!(None, instruction(&program, 5));
+    }
+}
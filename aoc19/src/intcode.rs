@@ -0,0 +1,362 @@
+// The Intcode computer that most of 2019 runs on. Memory is i64 and grows as it's written to, and
+// input/output go through queues so a program can be fed a bit at a time and paused whenever it
+// wants input that isn't there yet.
+
+use std::{collections::VecDeque, str::FromStr};
+
+use anyhow::Context;
+
+pub type Word = i64;
+
+/// Why the computer stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Hit opcode 99, it won't do anything else.
+    Halted,
+    /// Wants to read an input but the queue is empty. Push some input and resume.
+    NeedsInput,
+    /// Wrote an output.
+    Output(Word),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+impl Opcode {
+    /// Number of parameters that follow the instruction.
+    pub fn arity(&self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    /// Which parameter the instruction writes its result to, if any.
+    pub fn writes(&self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => Some(2),
+            Opcode::Input => Some(0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+/// Splits an instruction into its opcode and the mode of each of its parameters.
+pub fn decode(instruction: Word) -> anyhow::Result<(Opcode, [Mode; 3])> {
+    let op = match instruction % 100 {
+        1 => Opcode::Add,
+        2 => Opcode::Mul,
+        3 => Opcode::Input,
+        4 => Opcode::Output,
+        5 => Opcode::JumpIfTrue,
+        6 => Opcode::JumpIfFalse,
+        7 => Opcode::LessThan,
+        8 => Opcode::Equals,
+        9 => Opcode::AdjustBase,
+        99 => Opcode::Halt,
+        _ => anyhow::bail!("invalid instruction {instruction}"),
+    };
+    let mut modes = [Mode::Position; 3];
+    let mut digits = instruction / 100;
+    for mode in &mut modes {
+        *mode = match digits % 10 {
+            0 => Mode::Position,
+            1 => Mode::Immediate,
+            2 => Mode::Relative,
+            m => anyhow::bail!("invalid parameter mode {m} in instruction {instruction}"),
+        };
+        digits /= 10;
+    }
+    anyhow::ensure!(digits == 0, "invalid instruction {instruction}");
+    Ok((op, modes))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Intcode {
+    memory: Vec<Word>,
+    ip: usize,
+    relative_base: Word,
+    halted: bool,
+    input: VecDeque<Word>,
+    output: VecDeque<Word>,
+}
+
+impl FromStr for Intcode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let program = s
+            .trim()
+            .split(',')
+            .map(|n| {
+                n.trim()
+                    .parse()
+                    .with_context(|| format!("invalid intcode '{n}'"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Intcode::new(program))
+    }
+}
+
+impl Intcode {
+    pub fn new(program: Vec<Word>) -> Self {
+        Self {
+            memory: program,
+            ..Default::default()
+        }
+    }
+
+    /// The value at `addr`, memory that hasn't been written to yet is 0.
+    pub fn read(&self, addr: usize) -> Word {
+        self.memory.get(addr).copied().unwrap_or(0)
+    }
+
+    pub fn write(&mut self, addr: usize, value: Word) {
+        if addr >= self.memory.len() {
+            self.memory.resize(addr + 1, 0);
+        }
+        self.memory[addr] = value;
+    }
+
+    pub fn memory(&self) -> &[Word] {
+        &self.memory
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn relative_base(&self) -> Word {
+        self.relative_base
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Whether there's input queued up that hasn't been read yet.
+    pub fn has_input(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    pub fn push_input(&mut self, value: Word) {
+        self.input.push_back(value);
+    }
+
+    pub fn extend_input(&mut self, values: impl IntoIterator<Item = Word>) {
+        self.input.extend(values);
+    }
+
+    /// The oldest output collected by `run_until_blocked` that hasn't been taken yet.
+    pub fn pop_output(&mut self) -> Option<Word> {
+        self.output.pop_front()
+    }
+
+    /// Every output collected by `run_until_blocked` that hasn't been taken yet.
+    pub fn take_output(&mut self) -> Vec<Word> {
+        self.output.drain(..).collect()
+    }
+
+    /// The address the next instruction is going to write to, if it writes anything.
+    pub fn target(&self) -> anyhow::Result<Option<usize>> {
+        let (op, modes) = decode(self.read(self.ip))?;
+        op.writes().map(|n| self.addr(n, modes)).transpose()
+    }
+
+    /// Runs until the program halts, needs input it doesn't have, or outputs something.
+    pub fn resume(&mut self) -> anyhow::Result<State> {
+        loop {
+            if let Some(state) = self.step()? {
+                return Ok(state);
+            }
+        }
+    }
+
+    /// Runs until the program halts or needs input, queueing up everything it outputs on the way.
+    pub fn run_until_blocked(&mut self) -> anyhow::Result<State> {
+        loop {
+            match self.resume()? {
+                State::Output(value) => self.output.push_back(value),
+                state => return Ok(state),
+            }
+        }
+    }
+
+    /// Runs the program to completion with the given input and returns everything it output.
+    pub fn run(&mut self, input: impl IntoIterator<Item = Word>) -> anyhow::Result<Vec<Word>> {
+        self.extend_input(input);
+        match self.run_until_blocked()? {
+            State::Halted => Ok(self.take_output()),
+            _ => anyhow::bail!("ran out of input at {}", self.ip),
+        }
+    }
+
+    /// Executes a single instruction. Returns why the computer stopped if that instruction halted,
+    /// needed input or output something, otherwise `None`. When it needs input the instruction
+    /// isn't executed and will be tried again on the next step.
+    pub fn step(&mut self) -> anyhow::Result<Option<State>> {
+        if self.halted {
+            return Ok(Some(State::Halted));
+        }
+        let (op, modes) = decode(self.read(self.ip)).with_context(|| format!("at {}", self.ip))?;
+        let next = self.ip + 1 + op.arity();
+        let mut state = None;
+        let mut jump = None;
+        match op {
+            Opcode::Add => self.put(2, modes, self.get(0, modes)? + self.get(1, modes)?)?,
+            Opcode::Mul => self.put(2, modes, self.get(0, modes)? * self.get(1, modes)?)?,
+            Opcode::Input => {
+                let Some(value) = self.input.pop_front() else {
+                    return Ok(Some(State::NeedsInput));
+                };
+                self.put(0, modes, value)?;
+            }
+            Opcode::Output => state = Some(State::Output(self.get(0, modes)?)),
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                if (self.get(0, modes)? != 0) == (op == Opcode::JumpIfTrue) {
+                    jump = Some(self.get(1, modes)?);
+                }
+            }
+            Opcode::LessThan => {
+                let value = self.get(0, modes)? < self.get(1, modes)?;
+                self.put(2, modes, value as Word)?;
+            }
+            Opcode::Equals => {
+                let value = self.get(0, modes)? == self.get(1, modes)?;
+                self.put(2, modes, value as Word)?;
+            }
+            Opcode::AdjustBase => self.relative_base += self.get(0, modes)?,
+            Opcode::Halt => {
+                self.halted = true;
+                return Ok(Some(State::Halted));
+            }
+        }
+        self.ip = match jump {
+            Some(addr) => usize::try_from(addr)
+                .with_context(|| format!("jump to negative address {addr} at {}", self.ip))?,
+            None => next,
+        };
+        Ok(state)
+    }
+
+    // the address the n-th parameter of the current instruction refers to
+    fn addr(&self, n: usize, modes: [Mode; 3]) -> anyhow::Result<usize> {
+        let param = self.ip + 1 + n;
+        let addr = match modes[n] {
+            Mode::Position => self.read(param),
+            Mode::Immediate => return Ok(param),
+            Mode::Relative => self.relative_base + self.read(param),
+        };
+        usize::try_from(addr).with_context(|| format!("negative address {addr} at {}", self.ip))
+    }
+
+    fn get(&self, n: usize, modes: [Mode; 3]) -> anyhow::Result<Word> {
+        Ok(self.read(self.addr(n, modes)?))
+    }
+
+    fn put(&mut self, n: usize, modes: [Mode; 3], value: Word) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            modes[n] != Mode::Immediate,
+            "write to an immediate parameter at {}",
+            self.ip
+        );
+        let addr = self.addr(n, modes)?;
+        self.write(addr, value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn computer(program: &str) -> Intcode {
+        program.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("1,0,0,0,99", "2,0,0,0,99")]
+    #[case("2,4,4,5,99,0", "2,4,4,5,99,9801")]
+    #[case("1,1,1,4,99,5,6,0,99", "30,1,1,4,2,5,6,0,99")]
+    #[case("1002,4,3,4,33", "1002,4,3,4,99")]
+    #[case("1101,100,-1,4,0", "1101,100,-1,4,99")]
+    fn test_memory(#[case] program: &str, #[case] expected: &str) {
+        let mut vm = computer(program);
+        vm.run([]).unwrap();
+        assert_eq!(computer(expected).memory(), vm.memory());
+    }
+
+    #[rstest]
+    // position mode, is the input equal to 8
+    #[case("3,9,8,9,10,9,4,9,99,-1,8", 8, 1)]
+    #[case("3,9,8,9,10,9,4,9,99,-1,8", 7, 0)]
+    // immediate mode, is the input less than 8
+    #[case("3,3,1107,-1,8,3,4,3,99", 5, 1)]
+    #[case("3,3,1107,-1,8,3,4,3,99", 9, 0)]
+    // jumps, is the input non-zero
+    #[case("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9", 0, 0)]
+    #[case("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", 3, 1)]
+    fn test_compare(#[case] program: &str, #[case] input: Word, #[case] expected: Word) {
+        assert_eq!(vec![expected], computer(program).run([input]).unwrap());
+    }
+
+    #[test]
+    fn test_relative() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        assert_eq!(computer(quine).memory(), computer(quine).run([]).unwrap());
+        let big = computer("104,1125899906842624,99").run([]).unwrap();
+        assert_eq!(vec![1125899906842624], big);
+        let digits = computer("1102,34915192,34915192,7,4,7,99,0")
+            .run([])
+            .unwrap();
+        assert_eq!(16, digits[0].to_string().len());
+    }
+
+    #[test]
+    fn test_states() {
+        // echoes inputs until it reads a 0
+        let mut vm = computer("3,11,4,11,1005,11,0,99");
+        assert_eq!(State::NeedsInput, vm.resume().unwrap());
+        vm.push_input(5);
+        assert_eq!(State::Output(5), vm.resume().unwrap());
+        assert_eq!(State::NeedsInput, vm.resume().unwrap());
+        vm.extend_input([6, 7]);
+        assert_eq!(State::NeedsInput, vm.run_until_blocked().unwrap());
+        assert_eq!(vec![6, 7], vm.take_output());
+        vm.push_input(0);
+        assert_eq!(State::Output(0), vm.resume().unwrap());
+        assert_eq!(State::Halted, vm.resume().unwrap());
+        assert!(vm.is_halted());
+        assert_eq!(State::Halted, vm.resume().unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(computer("3,0,99").run([]).is_err());
+        assert!(computer("42").run([]).is_err());
+        assert!(computer("1101,1,1,-1,99").run([]).is_err());
+        assert!(computer("11101,1,1,0,99").run([]).is_err());
+        assert!("1,2,x".parse::<Intcode>().is_err());
+    }
+}
@@ -2,7 +2,7 @@ pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
-// pub mod day05;
+pub mod day05;
 // pub mod day06;
 // pub mod day07;
 // pub mod day08;
@@ -24,6 +24,11 @@ pub mod day04;
 // pub mod day24;
 // pub mod day25;
 
+pub mod disasm;
+pub mod intcode;
+pub mod network;
+pub mod trace;
+
 pub struct SolveInfo {
     pub part01: String,
     pub part02: String,
@@ -8,6 +8,9 @@ use aoc19::*;
 #[derive(Parser)]
 struct Cli {
     day: usize,
+    /// Print the day's input as annotated Intcode instead of solving it
+    #[arg(long)]
+    disassemble: bool,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -18,7 +21,7 @@ fn main() -> anyhow::Result<()> {
         day02::run,
         day03::run,
         day04::run,
-        // day05::run,
+        day05::run,
         // day06::run,
         // day07::run,
         // day08::run,
@@ -43,6 +46,12 @@ fn main() -> anyhow::Result<()> {
 
     let input = std::io::read_to_string(std::io::stdin()).context("could not read stdin")?;
 
+    if cli.disassemble {
+        let program: intcode::Intcode = input.parse()?;
+        print!("{}", disasm::disassemble(program.memory()));
+        return Ok(());
+    }
+
     let start = Instant::now();
     let f = days[cli.day - 1];
     let solve = f(&input)?;
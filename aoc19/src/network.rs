@@ -0,0 +1,281 @@
+// Runs a bunch of Intcode computers together, passing what each one outputs on to the inputs of
+// the others. The amplifier loops (day 7) and the packet network (day 23) are both just a way of
+// wiring the machines up.
+
+use crate::intcode::{Intcode, Word};
+
+pub type MachineId = usize;
+
+/// Where a message is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    Machine(MachineId),
+    /// Somewhere that isn't one of the machines, e.g. the NAT at 255. These messages are kept in
+    /// the outbox.
+    Outside(Word),
+}
+
+/// How a machine's output is split into messages and where they go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Every output is a message of its own, sent to the same address.
+    To(Address),
+    /// Outputs come in packets of an address followed by this many words. Addresses that aren't a
+    /// machine go outside.
+    Packets(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// `None` for messages sent in from outside with `send`.
+    pub from: Option<MachineId>,
+    pub to: Address,
+    pub payload: Vec<Word>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Every machine that's still running is waiting on input nobody is going to send.
+    Idle,
+    /// Every machine has halted.
+    Halted,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Network {
+    machines: Vec<Intcode>,
+    routes: Vec<Route>,
+    // words output towards a packet that isn't complete yet, per machine
+    partial: Vec<Vec<Word>>,
+    idle_input: Option<Word>,
+    outbox: Vec<Message>,
+    traffic: Vec<Message>,
+}
+
+impl Network {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// What a machine reads when it wants input and there isn't any, e.g. -1 on the packet
+    /// network. Without one, machines just wait.
+    pub fn with_idle_input(mut self, value: Word) -> Self {
+        self.idle_input = Some(value);
+        self
+    }
+
+    /// Adds a machine whose output goes along `route`.
+    pub fn add(&mut self, machine: Intcode, route: Route) -> MachineId {
+        self.machines.push(machine);
+        self.routes.push(route);
+        self.partial.push(Vec::new());
+        self.machines.len() - 1
+    }
+
+    pub fn machine(&self, id: MachineId) -> &Intcode {
+        &self.machines[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+
+    /// Sends a message in from outside the network.
+    pub fn send(&mut self, to: MachineId, payload: Vec<Word>) {
+        self.deliver(Message {
+            from: None,
+            to: Address::Machine(to),
+            payload,
+        });
+    }
+
+    /// Every message sent so far, in order.
+    pub fn traffic(&self) -> &[Message] {
+        &self.traffic
+    }
+
+    /// The messages that left the network since this was last called.
+    pub fn take_outbox(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Gives each machine that hasn't halted a turn, running it until it blocks. Returns whether
+    /// the whole network was idle, i.e. no machine had anything to read or sent anything.
+    pub fn round(&mut self) -> anyhow::Result<bool> {
+        let mut idle = true;
+        for id in 0..self.len() {
+            let machine = &mut self.machines[id];
+            if machine.is_halted() {
+                continue;
+            }
+            let waiting = !machine.has_input();
+            if let (true, Some(value)) = (waiting, self.idle_input) {
+                machine.push_input(value);
+            }
+            machine.run_until_blocked()?;
+            let output = machine.take_output();
+            idle &= waiting && output.is_empty();
+            for word in output {
+                self.route(id, word);
+            }
+        }
+        Ok(idle)
+    }
+
+    /// Runs rounds until the network is idle or everything has halted.
+    pub fn run(&mut self) -> anyhow::Result<Status> {
+        loop {
+            let idle = self.round()?;
+            if self.machines.iter().all(Intcode::is_halted) {
+                return Ok(Status::Halted);
+            }
+            if idle {
+                return Ok(Status::Idle);
+            }
+        }
+    }
+
+    fn route(&mut self, from: MachineId, word: Word) {
+        let (to, payload) = match self.routes[from] {
+            Route::To(to) => (to, vec![word]),
+            Route::Packets(len) => {
+                let partial = &mut self.partial[from];
+                partial.push(word);
+                if partial.len() < len + 1 {
+                    return;
+                }
+                let packet = std::mem::take(partial);
+                let to = match usize::try_from(packet[0]) {
+                    Ok(id) if id < self.machines.len() => Address::Machine(id),
+                    _ => Address::Outside(packet[0]),
+                };
+                (to, packet[1..].to_vec())
+            }
+        };
+        self.deliver(Message {
+            from: Some(from),
+            to,
+            payload,
+        });
+    }
+
+    fn deliver(&mut self, message: Message) {
+        match message.to {
+            Address::Machine(id) => self.machines[id].extend_input(message.payload.clone()),
+            Address::Outside(_) => self.outbox.push(message.clone()),
+        }
+        self.traffic.push(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // the day 7 amplifiers, each gets its phase first and passes its signal on to the next
+    fn amplify(program: &str, phases: &[Word], feedback: bool) -> Word {
+        let program: Intcode = program.parse().unwrap();
+        let mut network = Network::new();
+        for (i, &phase) in phases.iter().enumerate() {
+            let mut amp = program.clone();
+            amp.push_input(phase);
+            let next = if i + 1 < phases.len() {
+                Address::Machine(i + 1)
+            } else if feedback {
+                Address::Machine(0)
+            } else {
+                Address::Outside(0)
+            };
+            network.add(amp, Route::To(next));
+        }
+        network.send(0, vec![0]);
+        assert_eq!(Status::Halted, network.run().unwrap());
+        let last = network.len() - 1;
+        let signal = network
+            .traffic()
+            .iter()
+            .rev()
+            .find(|m| m.from == Some(last));
+        signal.unwrap().payload[0]
+    }
+
+    #[rstest]
+    #[case(
+        "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0",
+        &[4, 3, 2, 1, 0],
+        false,
+        43210
+    )]
+    #[case(
+        "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0",
+        &[0, 1, 2, 3, 4],
+        false,
+        54321
+    )]
+    #[case(
+        "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        &[9, 8, 7, 6, 5],
+        true,
+        139629729
+    )]
+    fn test_amplifiers(
+        #[case] program: &str,
+        #[case] phases: &[Word],
+        #[case] feedback: bool,
+        #[case] expected: Word,
+    ) {
+        assert_eq!(expected, amplify(program, phases, feedback));
+    }
+
+    #[test]
+    fn test_packets() {
+        // reads its address, then passes each packet (x, y) on to the next machine as (x + 1, y),
+        // with the last machine sending to 255 instead. Reading -1 means there's no packet.
+        //
+        //    0: in [100]                    17: in [102]
+        //    2: [101] = [100] + 1           19: [104] = [102] == -1
+        //    6: [102] = [101] == 3          23: jump-if-true [104] 17
+        //   10: jump-if-false [102] 17      26: in [103]
+        //   13: [101] = 0 + 255             28: [102] = [102] + 1
+        //                                   32: out [101], out [102], out [103]
+        //                                   38: jump 17
+        let program = "3,100,1001,100,1,101,1008,101,3,102,1006,102,17,1101,0,255,101,\
+                       3,102,1008,102,-1,104,1005,104,17,3,103,1001,102,1,102,\
+                       4,101,4,102,4,103,1105,1,17";
+        let program: Intcode = program.parse().unwrap();
+        let mut network = Network::new().with_idle_input(-1);
+        for id in 0..3 {
+            let mut machine = program.clone();
+            machine.push_input(id);
+            network.add(machine, Route::Packets(2));
+        }
+        network.send(0, vec![10, 20]);
+        assert_eq!(Status::Idle, network.run().unwrap());
+
+        let outbox = network.take_outbox();
+        assert_eq!(
+            vec![Message {
+                from: Some(2),
+                to: Address::Outside(255),
+                payload: vec![13, 20],
+            }],
+            outbox
+        );
+        let hops: Vec<_> = network.traffic().iter().map(|m| m.to).collect();
+        assert_eq!(
+            vec![
+                Address::Machine(0),
+                Address::Machine(1),
+                Address::Machine(2),
+                Address::Outside(255)
+            ],
+            hops
+        );
+        assert!(network.take_outbox().is_empty());
+    }
+}
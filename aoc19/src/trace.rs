@@ -0,0 +1,164 @@
+// Runs an Intcode computer one instruction at a time, logging every instruction it executes
+// along with what it wrote to memory and what went in and out. Useful for finding out why a
+// program isn't doing what it should.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::Context;
+use fxhash::FxHashSet;
+
+use crate::{
+    disasm,
+    intcode::{Intcode, State},
+};
+
+/// Why `Tracer::resume` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    State(State),
+    /// About to execute the instruction at a breakpoint.
+    Breakpoint(usize),
+}
+
+pub struct Tracer<W: Write> {
+    vm: Intcode,
+    log: W,
+    breakpoints: FxHashSet<usize>,
+}
+
+impl Tracer<BufWriter<File>> {
+    /// Traces `vm` into a file, replacing anything that was there.
+    pub fn to_file(vm: Intcode, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("could not create {path:?}"))?;
+        Ok(Tracer::new(vm, BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> Tracer<W> {
+    pub fn new(vm: Intcode, log: W) -> Self {
+        Self {
+            vm,
+            log,
+            breakpoints: Default::default(),
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, addr: usize) {
+        self.breakpoints.insert(addr);
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: usize) {
+        self.breakpoints.remove(&addr);
+    }
+
+    pub fn vm(&self) -> &Intcode {
+        &self.vm
+    }
+
+    /// For feeding input, or poking at memory while stopped at a breakpoint.
+    pub fn vm_mut(&mut self) -> &mut Intcode {
+        &mut self.vm
+    }
+
+    /// Stops tracing, flushing the log.
+    pub fn into_inner(mut self) -> anyhow::Result<(Intcode, W)> {
+        self.log.flush()?;
+        Ok((self.vm, self.log))
+    }
+
+    /// Like `Intcode::step`, but logs what happened.
+    pub fn step(&mut self) -> anyhow::Result<Option<State>> {
+        let ip = self.vm.ip();
+        if self.vm.is_halted() {
+            return Ok(Some(State::Halted));
+        }
+        let text = disasm::instruction(self.vm.memory(), ip)
+            .map_or_else(|| format!("data {}", self.vm.read(ip)), |(text, _)| text);
+        writeln!(self.log, "{ip:>5}: {text}")?;
+
+        let target = self.vm.target();
+        let state = self.vm.step();
+        if let Err(e) = &state {
+            writeln!(self.log, "       error: {e:#}")?;
+        }
+        let state = state?;
+        if state == Some(State::NeedsInput) {
+            writeln!(self.log, "       waiting for input")?;
+        } else if let Some(addr) = target? {
+            writeln!(self.log, "       [{addr}] <- {}", self.vm.read(addr))?;
+        }
+        match state {
+            Some(State::Output(value)) => writeln!(self.log, "       output {value}")?,
+            Some(State::Halted) => writeln!(self.log, "       halted")?,
+            _ => (),
+        }
+        Ok(state)
+    }
+
+    /// Like `Intcode::resume`, but also stops before executing an instruction with a breakpoint
+    /// on it. The instruction it starts on is always executed, so calling this again carries on
+    /// from a breakpoint.
+    pub fn resume(&mut self) -> anyhow::Result<Stop> {
+        loop {
+            if let Some(state) = self.step()? {
+                return Ok(Stop::State(state));
+            }
+            let ip = self.vm.ip();
+            if self.breakpoints.contains(&ip) {
+                writeln!(self.log, "       breakpoint at {ip}")?;
+                return Ok(Stop::Breakpoint(ip));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        // doubles its input until it reaches 100
+        let vm: Intcode = "3,16,1002,16,2,16,1007,16,100,17,1005,17,2,4,16,99"
+            .parse()
+            .unwrap();
+        let mut tracer = Tracer::new(vm, Vec::new());
+        tracer.add_breakpoint(6);
+        assert_eq!(Stop::State(State::NeedsInput), tracer.resume().unwrap());
+        tracer.vm_mut().push_input(30);
+        assert_eq!(Stop::Breakpoint(6), tracer.resume().unwrap());
+        assert_eq!(60, tracer.vm().read(16));
+        tracer.remove_breakpoint(6);
+        assert_eq!(Stop::State(State::Output(120)), tracer.resume().unwrap());
+        assert_eq!(Stop::State(State::Halted), tracer.resume().unwrap());
+
+        let (_, log) = tracer.into_inner().unwrap();
+        let log = String::from_utf8(log).unwrap();
+        let expected = "    0: in   [16]                        ; [16] = input
+       waiting for input
+    0: in   [16]                        ; [16] = input
+       [16] <- 30
+    2: mul  [16], 2, [16]               ; [16] = [16] * 2
+       [16] <- 60
+       breakpoint at 6
+    6: lt   [16], 100, [17]             ; [17] = [16] < 100
+       [17] <- 1
+   10: jt   [17], 2                     ; if [17] != 0 goto 2
+    2: mul  [16], 2, [16]               ; [16] = [16] * 2
+       [16] <- 120
+    6: lt   [16], 100, [17]             ; [17] = [16] < 100
+       [17] <- 0
+   10: jt   [17], 2                     ; if [17] != 0 goto 2
+   13: out  [16]                        ; output [16]
+       output 120
+   15: halt
+       halted
+";
+        assert_eq!(expected, log);
+    }
+}
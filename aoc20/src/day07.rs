@@ -1,5 +1,5 @@
-use fxhash::{FxHashMap, FxHashSet};
-use std::collections::VecDeque;
+use common::graph::Graph;
+use fxhash::FxHashMap;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -9,66 +9,46 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
-    // child -> [parent]
-    let mut graph: FxHashMap<&str, Vec<&str>> = FxHashMap::default();
-    graph.reserve(512);
+    // child -> parent
+    let mut graph: Graph<&str> = Graph::directed();
     for (parent, child, _) in parse_input(input) {
-        graph.entry(child).or_default().push(parent);
+        graph.add_edge(child, parent);
     }
 
-    let mut queue = VecDeque::with_capacity(graph.len());
-    queue.push_front("shiny gold");
-    let mut seen = FxHashSet::default();
-    seen.reserve(graph.len());
-    let mut num_bags = 0;
-    while let Some(curr) = queue.pop_front() {
-        for &v in graph.get(curr).into_iter().flatten() {
-            if !seen.insert(v) {
-                continue;
-            }
-            num_bags += 1;
-            queue.push_back(v);
-        }
-    }
-
-    Ok(num_bags)
+    Ok(graph
+        .id(&"shiny gold")
+        .map_or(0, |gold| graph.reachable(gold).len()))
 }
 
 pub fn part02(input: &str) -> anyhow::Result<usize> {
-    fn count_bags<'a>(
-        bag: &'a str,
-        graph: &FxHashMap<&'a str, Vec<(&'a str, usize)>>,
-        memo: &mut FxHashMap<&'a str, usize>,
+    fn count_bags(
+        bag: usize,
+        graph: &Graph<&str, usize>,
+        memo: &mut FxHashMap<usize, usize>,
     ) -> usize {
-        if let Some(&count) = memo.get(bag) {
+        if let Some(&count) = memo.get(&bag) {
             return count;
         }
 
-        let Some(children) = graph.get(bag) else {
-            return 0;
-        };
-
         let mut count = 0;
-        for &(child, n) in children {
+        for &(child, n) in graph.out_edges(bag) {
             count += n * (1 + count_bags(child, graph, memo));
         }
         memo.insert(bag, count);
         count
     }
 
-    // parent -> [(child, count)]
-    let mut graph: FxHashMap<&str, Vec<(&str, usize)>> = FxHashMap::default();
-    graph.reserve(512);
+    // parent -> (child, count)
+    let mut graph = Graph::directed();
     for (parent, child, n) in parse_input(input) {
-        graph
-            .entry(parent)
-            .or_insert_with(|| Vec::with_capacity(4))
-            .push((child, n));
+        graph.add_weighted_edge(parent, child, n);
     }
 
     let mut memo = FxHashMap::default();
     memo.reserve(graph.len());
-    Ok(count_bags("shiny gold", &graph, &mut memo))
+    Ok(graph
+        .id(&"shiny gold")
+        .map_or(0, |gold| count_bags(gold, &graph, &mut memo)))
 }
 
 fn parse_input(input: &str) -> impl Iterator<Item = (&str, &str, usize)> {
@@ -103,6 +83,23 @@ mod tests {
 
     const INPUT: &str = include_str!("../inputs/day07.input.txt");
 
+    const SAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(4, part01(SAMPLE).unwrap());
+        assert_eq!(32, part02(SAMPLE).unwrap());
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT).unwrap();
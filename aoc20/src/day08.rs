@@ -1,4 +1,5 @@
-use bittle::{Bits, BitsMut};
+use anyhow::Context;
+use common::asm::{Exit, Flow, Instruction, Machine};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -8,88 +9,65 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<isize> {
-    let ops = parse_input(input);
-    let (acc, _) = run_until_cycle(&ops);
-    Ok(acc)
+    let mut console = Machine::<Op>::parse(input, 0)?;
+    match console.run_until_loop() {
+        Exit::Loop(_) => Ok(console.state),
+        Exit::Halted => anyhow::bail!("the program doesn't loop"),
+    }
 }
 
 pub fn part02(input: &str) -> anyhow::Result<isize> {
-    let mut ops = parse_input(input);
-
-    for mut_idx in 0..ops.len() {
-        if let Op::Acc = ops[mut_idx].0 {
-            continue;
-        }
-
-        flip(&mut ops[mut_idx].0);
-        let (acc, did_cycle) = run_until_cycle(&ops);
-        flip(&mut ops[mut_idx].0);
-
-        if !did_cycle {
-            return Ok(acc);
-        }
-    }
-    panic!("no solution found")
+    let console = Machine::<Op>::parse(input, 0)?;
+    // exactly one nop should be a jmp or the other way round
+    let patch = console
+        .find_patch(|op| match *op {
+            Op::Nop(delta) => vec![Op::Jmp(delta)],
+            Op::Jmp(delta) => vec![Op::Nop(delta)],
+            Op::Acc(_) => vec![],
+        })?
+        .context("no solution found")?;
+    Ok(patch.machine.state)
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 enum Op {
-    Nop,
-    Acc,
-    Jmp,
+    Nop(isize),
+    Acc(isize),
+    Jmp(isize),
 }
 
-fn run_until_cycle(ops: &[(Op, isize)]) -> (isize, bool) {
-    let mut acc = 0;
-    let mut ip = 0;
-    let mut seen = vec![0u64; ops.len().div_ceil(u64::BITS as usize)];
-
-    while ip < ops.len() && !seen.test_bit(ip as u32) {
-        seen.set_bit(ip as u32);
-
-        let (op, delta) = ops[ip];
+impl Instruction for Op {
+    // the accumulator
+    type State = isize;
+
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        let (op, delta) = line.split_once(' ').context("no argument")?;
+        let delta = delta.parse()?;
+        Ok(match op {
+            "nop" => Op::Nop(delta),
+            "acc" => Op::Acc(delta),
+            "jmp" => Op::Jmp(delta),
+            _ => anyhow::bail!("unknown op {op}"),
+        })
+    }
 
-        match op {
-            Op::Nop => ip += 1,
-            Op::Acc => {
-                acc += delta;
-                ip += 1;
-            }
-            Op::Jmp => {
-                ip = ip.checked_add_signed(delta).expect("delta underflowed ip");
+    fn execute(&self, acc: &mut isize) -> Flow {
+        match self {
+            Op::Nop(_) => Flow::Next,
+            Op::Acc(delta) => {
+                *acc += delta;
+                Flow::Next
             }
+            Op::Jmp(delta) => Flow::Jump(*delta),
         }
     }
 
-    (acc, ip < ops.len())
-}
-
-fn flip(op: &mut Op) {
-    *op = match op {
-        Op::Nop => Op::Jmp,
-        Op::Jmp => Op::Nop,
-        Op::Acc => unreachable!(),
-    };
-}
-
-fn parse_input(input: &str) -> Vec<(Op, isize)> {
-    let mut ops = Vec::with_capacity(input.lines().count());
-    for line in input.lines() {
-        let (op, delta) = line.split_once(' ').unwrap();
-        let op = match op {
-            "nop" => Op::Nop,
-            "acc" => Op::Acc,
-            "jmp" => Op::Jmp,
-            _ => panic!("unknown op {op}"),
-        };
-        let delta = delta
-            .strip_prefix('+')
-            .unwrap_or(delta)
-            .parse::<isize>()
-            .unwrap();
-        ops.push((op, delta));
+    fn static_flow(&self) -> Option<Flow> {
+        Some(match self {
+            Op::Nop(_) | Op::Acc(_) => Flow::Next,
+            Op::Jmp(delta) => Flow::Jump(*delta),
+        })
     }
-    ops
 }
 
 #[cfg(test)]
@@ -97,6 +75,21 @@ mod tests {
     use super::*;
 
     const INPUT: &str = include_str!("../inputs/day08.input.txt");
+    const SAMPLE: &str = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(5, part01(SAMPLE).unwrap());
+        assert_eq!(8, part02(SAMPLE).unwrap());
+    }
 
     #[test]
     fn test_part_one() {
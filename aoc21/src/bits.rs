@@ -0,0 +1,541 @@
+// The Buoyancy Interchange Transmission System from day 16: a tree of packets packed into bits and
+// sent as hex. Decodes transmissions into `Packet`s and encodes them back, so anything built here
+// can be checked by round-tripping it.
+
+use std::fmt;
+
+use anyhow::{Context, bail, ensure};
+
+/// Reads big-endian numbers of any width from a string of bits.
+pub struct BitReader {
+    bytes: Vec<u8>,
+    len: usize,
+    pos: usize,
+}
+
+impl BitReader {
+    /// Reads the bits of a hex transmission. Surrounding whitespace is ignored.
+    pub fn from_hex(hex: &str) -> anyhow::Result<Self> {
+        let mut writer = BitWriter::new();
+        for (i, ch) in hex.trim().chars().enumerate() {
+            let digit = ch
+                .to_digit(16)
+                .with_context(|| format!("'{ch}' at {i} isn't a hex digit"))?;
+            writer.write(digit.into(), 4)?;
+        }
+        Ok(writer.into_reader())
+    }
+
+    /// The index of the next bit to read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.pos
+    }
+
+    /// Reads the next `nbits` bits (at most 64) as a number.
+    pub fn read(&mut self, nbits: usize) -> anyhow::Result<u64> {
+        ensure!(nbits <= 64, "can't read {nbits} bits at once");
+        ensure!(
+            nbits <= self.remaining(),
+            "ran out of bits at bit {}: wanted {nbits}, {} left",
+            self.pos,
+            self.remaining()
+        );
+        let mut n = 0;
+        for _ in 0..nbits {
+            let bit = self.bytes[self.pos / 8] >> (7 - self.pos % 8) & 1;
+            n = n << 1 | u64::from(bit);
+            self.pos += 1;
+        }
+        Ok(n)
+    }
+
+    pub fn read_bit(&mut self) -> anyhow::Result<bool> {
+        Ok(self.read(1)? == 1)
+    }
+}
+
+/// Builds up a string of bits.
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Number of bits written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the low `nbits` bits of `value`, failing if it doesn't fit.
+    pub fn write(&mut self, value: u64, nbits: usize) -> anyhow::Result<()> {
+        ensure!(
+            nbits <= 64 && (nbits == 64 || value >> nbits == 0),
+            "{value} doesn't fit in {nbits} bits"
+        );
+        for i in (0..nbits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = (value >> i & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// The bits as hex, padded with zeros to a whole number of bytes like the puzzle's
+    /// transmissions are.
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|b| format!("{b:02X}")).collect()
+    }
+
+    /// Writes everything in `other` after what's already here.
+    pub fn append(&mut self, other: BitWriter) {
+        let len = other.len;
+        let mut bits = other.into_reader();
+        for _ in 0..len {
+            self.write(bits.read(1).unwrap(), 1).unwrap();
+        }
+    }
+
+    pub fn into_reader(self) -> BitReader {
+        BitReader {
+            bytes: self.bytes,
+            len: self.len,
+            pos: 0,
+        }
+    }
+}
+
+/// What an operator packet does with the values of its sub-packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Op {
+    fn from_type_id(id: u64) -> Option<Self> {
+        Some(match id {
+            0 => Op::Sum,
+            1 => Op::Product,
+            2 => Op::Minimum,
+            3 => Op::Maximum,
+            5 => Op::GreaterThan,
+            6 => Op::LessThan,
+            7 => Op::EqualTo,
+            _ => return None,
+        })
+    }
+
+    fn type_id(self) -> u64 {
+        match self {
+            Op::Sum => 0,
+            Op::Product => 1,
+            Op::Minimum => 2,
+            Op::Maximum => 3,
+            Op::GreaterThan => 5,
+            Op::LessThan => 6,
+            Op::EqualTo => 7,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Op::Sum => "+",
+            Op::Product => "*",
+            Op::Minimum => "min",
+            Op::Maximum => "max",
+            Op::GreaterThan => ">",
+            Op::LessThan => "<",
+            Op::EqualTo => "=",
+        }
+    }
+}
+
+/// How an operator packet says where its sub-packets end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    /// The total number of bits in them, in 15 bits.
+    Bits,
+    /// The number of sub-packets, in 11 bits.
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Literal(u64),
+    Operator {
+        op: Op,
+        length: Length,
+        packets: Vec<Packet>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// 3 bits.
+    pub version: u8,
+    pub body: Body,
+}
+
+const LITERAL: u64 = 4;
+
+impl Packet {
+    /// Decodes the outermost packet of a hex transmission, ignoring the padding after it.
+    pub fn from_hex(hex: &str) -> anyhow::Result<Self> {
+        Packet::decode(&mut BitReader::from_hex(hex)?)
+    }
+
+    /// Decodes the packet starting at the reader's position.
+    pub fn decode(bits: &mut BitReader) -> anyhow::Result<Self> {
+        let start = bits.position();
+        Packet::decode_at(bits).with_context(|| format!("packet at bit {start}"))
+    }
+
+    fn decode_at(bits: &mut BitReader) -> anyhow::Result<Self> {
+        let version = bits.read(3)? as u8;
+        let type_id = bits.read(3)?;
+        if type_id == LITERAL {
+            let mut n = 0u64;
+            loop {
+                let more = bits.read_bit()?;
+                ensure!(
+                    n >> 60 == 0,
+                    "literal at bit {} is too big",
+                    bits.position()
+                );
+                n = n << 4 | bits.read(4)?;
+                if !more {
+                    break;
+                }
+            }
+            return Ok(Packet {
+                version,
+                body: Body::Literal(n),
+            });
+        }
+
+        let op = Op::from_type_id(type_id).context("unknown type id")?;
+        let mut packets = Vec::new();
+        let length = if bits.read_bit()? {
+            for _ in 0..bits.read(11)? {
+                packets.push(Packet::decode(bits)?);
+            }
+            Length::Count
+        } else {
+            let len = bits.read(15)? as usize;
+            let end = bits.position() + len;
+            while bits.position() < end {
+                packets.push(Packet::decode(bits)?);
+            }
+            ensure!(
+                bits.position() == end,
+                "sub-packets should have ended at bit {end} but went on to {}",
+                bits.position()
+            );
+            Length::Bits
+        };
+        Ok(Packet {
+            version,
+            body: Body::Operator {
+                op,
+                length,
+                packets,
+            },
+        })
+    }
+
+    /// Appends the packet's bits to `bits`.
+    pub fn encode(&self, bits: &mut BitWriter) -> anyhow::Result<()> {
+        bits.write(self.version.into(), 3)?;
+        match &self.body {
+            Body::Literal(n) => {
+                bits.write(LITERAL, 3)?;
+                let groups = (64 - n.leading_zeros() as usize).div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    bits.write(u64::from(i > 0), 1)?;
+                    bits.write(n >> (4 * i) & 0xf, 4)?;
+                }
+            }
+            Body::Operator {
+                op,
+                length,
+                packets,
+            } => {
+                bits.write(op.type_id(), 3)?;
+                match length {
+                    Length::Count => {
+                        bits.write(1, 1)?;
+                        bits.write(packets.len() as u64, 11)
+                            .context("too many sub-packets")?;
+                        for p in packets {
+                            p.encode(bits)?;
+                        }
+                    }
+                    Length::Bits => {
+                        let mut sub = BitWriter::new();
+                        for p in packets {
+                            p.encode(&mut sub)?;
+                        }
+                        bits.write(0, 1)?;
+                        bits.write(sub.len() as u64, 15)
+                            .context("sub-packets are too long")?;
+                        bits.append(sub);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_hex(&self) -> anyhow::Result<String> {
+        let mut bits = BitWriter::new();
+        self.encode(&mut bits)?;
+        Ok(bits.to_hex())
+    }
+
+    /// The sum of the versions of this packet and everything in it.
+    pub fn version_sum(&self) -> u64 {
+        let inner = match &self.body {
+            Body::Literal(_) => 0,
+            Body::Operator { packets, .. } => packets.iter().map(Packet::version_sum).sum(),
+        };
+        u64::from(self.version) + inner
+    }
+
+    /// What the expression evaluates to.
+    pub fn eval(&self) -> anyhow::Result<u64> {
+        let (op, packets) = match &self.body {
+            Body::Literal(n) => return Ok(*n),
+            Body::Operator { op, packets, .. } => (*op, packets),
+        };
+        let values = packets
+            .iter()
+            .map(Packet::eval)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let overflow = || format!("{self} overflows");
+        Ok(match (op, &values[..]) {
+            (_, []) => bail!("{self} has no operands"),
+            (Op::Sum, _) => values
+                .iter()
+                .try_fold(0u64, |a, &b| a.checked_add(b))
+                .with_context(overflow)?,
+            (Op::Product, _) => values
+                .iter()
+                .try_fold(1u64, |a, &b| a.checked_mul(b))
+                .with_context(overflow)?,
+            (Op::Minimum, _) => *values.iter().min().unwrap(),
+            (Op::Maximum, _) => *values.iter().max().unwrap(),
+            (Op::GreaterThan, [a, b]) => u64::from(a > b),
+            (Op::LessThan, [a, b]) => u64::from(a < b),
+            (Op::EqualTo, [a, b]) => u64::from(a == b),
+            _ => bail!("{self} needs exactly two operands"),
+        })
+    }
+}
+
+/// The packet as an S-expression, e.g. `(+ 1 (* 2 3))`. The alternate form (`{:#}`) also shows
+/// each packet's version, as in `(+@5 1@0 2@3)`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = |f: &mut fmt::Formatter<'_>| {
+            if f.alternate() {
+                write!(f, "@{}", self.version)
+            } else {
+                Ok(())
+            }
+        };
+        match &self.body {
+            Body::Literal(n) => {
+                write!(f, "{n}")?;
+                version(f)
+            }
+            Body::Operator { op, packets, .. } => {
+                write!(f, "({}", op.name())?;
+                version(f)?;
+                for p in packets {
+                    write!(f, " ")?;
+                    fmt::Display::fmt(p, f)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    fn literal(version: u8, n: u64) -> Packet {
+        Packet {
+            version,
+            body: Body::Literal(n),
+        }
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut bits = BitReader::from_hex("D2FE28").unwrap();
+        assert_eq!(24, bits.remaining());
+        assert_eq!(0b110, bits.read(3).unwrap());
+        assert_eq!(0b100101111111000101000, bits.read(21).unwrap());
+        let err = bits.read(1).unwrap_err();
+        assert_eq!(
+            "ran out of bits at bit 24: wanted 1, 0 left",
+            err.to_string()
+        );
+
+        let mut writer = BitWriter::new();
+        writer.write(0b110, 3).unwrap();
+        writer.write(0b100, 3).unwrap();
+        writer.write(0b10111, 5).unwrap();
+        assert_eq!(11, writer.len());
+        assert_eq!("D2E0", writer.to_hex());
+        assert!(writer.write(8, 3).is_err());
+
+        assert!(BitReader::from_hex("D2G").is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(literal(6, 2021), Packet::from_hex("D2FE28").unwrap());
+        let expected = Packet {
+            version: 7,
+            body: Body::Operator {
+                op: Op::Maximum,
+                length: Length::Count,
+                packets: vec![literal(2, 1), literal(4, 2), literal(1, 3)],
+            },
+        };
+        assert_eq!(expected, Packet::from_hex("EE00D40C823060").unwrap());
+
+        let packet = Packet::from_hex("38006F45291200").unwrap();
+        assert_eq!(
+            Body::Operator {
+                op: Op::LessThan,
+                length: Length::Bits,
+                packets: vec![literal(6, 10), literal(2, 20)],
+            },
+            packet.body
+        );
+    }
+
+    #[rstest]
+    #[case("C200B40A82", "(+ 1 2)")]
+    #[case("04005AC33890", "(* 6 9)")]
+    #[case("9C0141080250320F1802104A08", "(= (+ 1 3) (* 2 2))")]
+    #[case("8A004A801A8002F478", "(min (min (min 15)))")]
+    fn test_display(#[case] hex: &str, #[case] expected: &str) {
+        assert_eq!(expected, Packet::from_hex(hex).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_display_versions() {
+        let packet = Packet::from_hex("EE00D40C823060").unwrap();
+        assert_eq!("(max@7 1@2 2@4 3@1)", format!("{packet:#}"));
+    }
+
+    #[rstest]
+    #[case("D2FE28")]
+    #[case("38006F45291200")]
+    #[case("EE00D40C823060")]
+    #[case("9C0141080250320F1802104A08")]
+    fn test_encode(#[case] hex: &str) {
+        assert_eq!(hex, Packet::from_hex(hex).unwrap().to_hex().unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        // the literal in D2FE28 with its last group cut off
+        let err = Packet::from_hex("D2FE").unwrap_err();
+        assert_eq!(
+            "packet at bit 0: ran out of bits at bit 16: wanted 1, 0 left",
+            format!("{err:#}")
+        );
+        // the second sub-packet of EE00D40C823060 cut off
+        let err = Packet::from_hex("EE00D40C8").unwrap_err();
+        assert_eq!(
+            "packet at bit 0: packet at bit 29: ran out of bits at bit 36: wanted 4, 0 left",
+            format!("{err:#}")
+        );
+        let less_than_three = Packet {
+            version: 0,
+            body: Body::Operator {
+                op: Op::LessThan,
+                length: Length::Count,
+                packets: vec![literal(0, 1), literal(0, 2), literal(0, 3)],
+            },
+        };
+        assert!(less_than_three.eval().is_err());
+    }
+
+    fn packets() -> impl Strategy<Value = Packet> {
+        let leaf = (0..8u8, any::<u64>()).prop_map(|(v, n)| literal(v, n));
+        leaf.prop_recursive(4, 32, 6, |inner| {
+            let op = prop_oneof![
+                Just(Op::Sum),
+                Just(Op::Product),
+                Just(Op::Minimum),
+                Just(Op::Maximum),
+                Just(Op::GreaterThan),
+                Just(Op::LessThan),
+                Just(Op::EqualTo),
+            ];
+            let length = prop_oneof![Just(Length::Bits), Just(Length::Count)];
+            (0..8u8, op, length, prop::collection::vec(inner, 0..6)).prop_map(
+                |(version, op, length, packets)| Packet {
+                    version,
+                    body: Body::Operator {
+                        op,
+                        length,
+                        packets,
+                    },
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(packet in packets()) {
+            let hex = packet.to_hex().unwrap();
+            prop_assert_eq!(&packet, &Packet::from_hex(&hex).unwrap());
+            prop_assert_eq!(hex.clone(), Packet::from_hex(&hex).unwrap().to_hex().unwrap());
+        }
+
+        #[test]
+        fn test_bits_round_trip(values in prop::collection::vec((any::<u64>(), 1..=64usize), 0..20)) {
+            let mut writer = BitWriter::new();
+            let values: Vec<_> = values.into_iter().map(|(v, n)| (v >> (64 - n), n)).collect();
+            for &(v, n) in &values {
+                writer.write(v, n).unwrap();
+            }
+            let mut reader = BitReader::from_hex(&writer.to_hex()).unwrap();
+            for &(v, n) in &values {
+                prop_assert_eq!(v, reader.read(n).unwrap());
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use common::graph::Graph;
 
 use crate::SolveInfo;
 
@@ -10,86 +10,94 @@ pub fn run(input: &str) -> anyhow::Result<SolveInfo> {
 }
 
 pub fn part01(input: &str) -> i64 {
-    let edges = parse_input(input);
-    let mut visits = HashMap::new();
-    visits.insert("start", 1);
-    enumerate(&edges, &Vec::new(), "start", &visits, 1)
+    Caves::new(input).paths(false)
 }
 
 pub fn part02(input: &str) -> i64 {
-    let edges = parse_input(input);
-    let mut visits = HashMap::new();
-    visits.insert("start", 2);
-    enumerate(&edges, &Vec::new(), "start", &visits, 2)
+    Caves::new(input).paths(true)
 }
 
-fn enumerate<'a>(
-    edges: &HashMap<&'a str, Vec<&'a str>>,
-    path: &[&'a str],
-    current_cave: &'a str,
-    visits: &HashMap<&'a str, i64>,
-    max_visits: i64,
-) -> i64 {
-    let mut path = path.to_owned();
-    path.push(current_cave);
-    println!("{:?} - {:?}", path, visits);
-
-    if current_cave == "end" {
-        return 1;
+struct Caves<'a> {
+    graph: Graph<&'a str>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Caves<'a> {
+    fn new(input: &'a str) -> Self {
+        let mut graph = Graph::undirected();
+        for line in input.lines() {
+            let (left, right) = line.split_once('-').unwrap();
+            graph.add_edge(left, right);
+        }
+        assert!(graph.len() <= 64, "too many caves for a u64 bitmask");
+        let start = graph.id(&"start").unwrap();
+        let end = graph.id(&"end").unwrap();
+        Caves { graph, start, end }
     }
 
-    let mut count = 0;
-    for neighbor in edges.get(current_cave).unwrap() {
-        // we shadow these variables here so that they are unique from this part of the path onward
-        let mut visits = visits.clone();
-        let mut max_visits = max_visits;
-
-        if is_small_cave(neighbor) {
-            // check small cave to determine if we have visited it more times than allowed
-            // if so then we end this path here and pop this neighbor off the stack
-            let num_visits = visits.entry(neighbor).or_default();
-            if *num_visits >= max_visits {
-                continue;
-            }
+    // the number of paths from start to end that visit small caves at most once, except that one
+    // small cave (other than start) may be visited twice if `revisit` is set
+    fn paths(&self, revisit: bool) -> i64 {
+        self.enumerate(self.start, 1 << self.start, revisit)
+    }
 
-            // we are going to visit this cave
-            *num_visits += 1;
+    fn enumerate(&self, cave: usize, visited: u64, revisit: bool) -> i64 {
+        if cave == self.end {
+            return 1;
+        }
 
-            // if we have visited this small cave more than once, lower the max visits
-            // so that no more caves can be visited more than once
-            if *num_visits > 1 {
-                max_visits = 1;
+        let mut count = 0;
+        for neighbor in self.graph.neighbours(cave) {
+            if !is_small_cave(self.graph.name(neighbor)) {
+                count += self.enumerate(neighbor, visited, revisit);
+            } else if visited & (1 << neighbor) == 0 {
+                count += self.enumerate(neighbor, visited | (1 << neighbor), revisit);
+            } else if revisit && neighbor != self.start {
+                // use up the one second visit
+                count += self.enumerate(neighbor, visited, false);
             }
         }
-
-        // count the total number of paths that start with the currently active path, that make it
-        // to "end"
-        count += enumerate(edges, &path, neighbor, &visits, max_visits);
+        count
     }
-
-    count
 }
 
 fn is_small_cave(cave: &str) -> bool {
     cave.chars().next().unwrap().is_lowercase()
 }
 
-fn parse_input(input: &str) -> HashMap<&str, Vec<&str>> {
-    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
-    for line in input.lines() {
-        let (left, right) = line.split_once('-').unwrap();
-        edges.entry(left).or_default().push(right);
-        edges.entry(right).or_default().push(left);
-    }
-    edges
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const INPUT: &str = include_str!("../inputs/day12.input.txt");
 
+    const SAMPLE: &str = "\
+fs-end
+he-DX
+fs-he
+start-DX
+pj-DX
+end-zg
+zg-sl
+zg-pj
+pj-he
+RW-he
+fs-DX
+pj-RW
+zg-RW
+start-pj
+he-WI
+zg-he
+pj-fs
+start-RW";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(226, part01(SAMPLE));
+        assert_eq!(3509, part02(SAMPLE));
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT);
@@ -1,12 +1,14 @@
 use std::collections::HashSet;
 use std::fmt::Write;
 
+use common::ocr;
+
 use crate::SolveInfo;
 
 pub fn run(input: &str) -> anyhow::Result<SolveInfo> {
     Ok(SolveInfo {
         part01: part01(input).to_string(),
-        part02: format!("\n{}", part02(input)),
+        part02: part02(input),
     })
 }
 
@@ -18,7 +20,7 @@ pub fn part01(input: &str) -> i64 {
 pub fn part02(input: &str) -> String {
     let (points, folds) = parse_input(input);
     let grid = execute_folds(&points, &folds);
-    stringify_grid(&grid)
+    ocr::read_or_art(&stringify_grid(&grid))
 }
 
 enum Fold {
@@ -140,13 +142,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let ans = part02(INPUT);
-        let expected = r#"
-###  #### ####   ## #  # ###  #### ####
-#  #    # #       # #  # #  # #       #
-#  #   #  ###     # #### #  # ###    # 
-###   #   #       # #  # ###  #     #  
-#    #    #    #  # #  # # #  #    #   
-#    #### #     ##  #  # #  # #    ####"#;
-        assert_eq!(expected.trim_start(), ans);
+        assert_eq!("PZEJHREZ", ans);
     }
 }
@@ -1,5 +1,4 @@
-use std::{fmt::Display, str::FromStr};
-
+use common::nested::Nested;
 use itertools::Itertools;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
@@ -9,197 +8,88 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     })
 }
 
-pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let complete = input
-        .lines()
-        .flat_map(Number::from_str)
-        .reduce(|acc, next| {
-            let mut n = acc.add(next);
-            n.reduce();
-            n
-        })
-        .unwrap();
-    Ok(complete.magnitude())
+pub fn part01(input: &str) -> anyhow::Result<i64> {
+    let numbers = parse_input(input)?;
+    let complete = numbers.into_iter().reduce(add).unwrap();
+    Ok(magnitude(&complete))
 }
 
-pub fn part02(input: &str) -> anyhow::Result<usize> {
-    Ok(input
-        .lines()
-        .flat_map(Number::from_str)
+pub fn part02(input: &str) -> anyhow::Result<i64> {
+    Ok(parse_input(input)?
+        .into_iter()
         .permutations(2)
-        .map(|pair| {
-            pair.into_iter()
-                .reduce(|acc, next| {
-                    let mut n = acc.add(next);
-                    n.reduce();
-                    n
-                })
-                .unwrap()
-        })
-        .map(|n| n.magnitude())
+        .map(|pair| magnitude(&pair.into_iter().reduce(add).unwrap()))
         .max()
         .unwrap())
 }
 
-#[derive(Debug, Clone)]
-enum Number {
-    Literal(usize),
-    Pair(Box<Number>, Box<Number>),
-}
-
-impl Number {
-    fn add(self, other: Number) -> Number {
-        Number::Pair(Box::new(self), Box::new(other))
-    }
-
-    fn magnitude(&self) -> usize {
-        match self {
-            Number::Literal(n) => *n,
-            Number::Pair(l, r) => 3 * l.magnitude() + 2 * r.magnitude(),
-        }
-    }
-
-    fn reduce(&mut self) {
-        loop {
-            let mut exploded = false;
-            // explode any numbers of depth 4
-            while self.maybe_explode(0).is_some() {
-                exploded = true;
-            }
-
-            let split = self.split();
-
-            if !exploded && !split {
-                break;
-            }
-        }
-    }
-
-    fn maybe_explode(&mut self, depth: usize) -> Option<(bool, usize, usize)> {
-        if let Number::Pair(l, r) = self {
-            if depth == 4 {
-                let Number::Pair(l, r) = self else {
-                    unreachable!("unexpected structure at depth 4: {self:?}");
-                };
-                let Number::Literal(l) = **l else {
-                    unreachable!("unexpected structure at depth 4: {self:?}");
-                };
-                let Number::Literal(r) = **r else {
-                    unreachable!("unexpected structure at depth 4: {self:?}");
-                };
-                return Some((true, l, r));
-            }
-
-            if let Some((deleteme, dl, mut dr)) = l.maybe_explode(depth + 1) {
-                if dr > 0 && r.explode_add_left(dr) {
-                    dr = 0;
-                }
-                if deleteme {
-                    *l = Box::new(Number::Literal(0));
-                }
-                return Some((false, dl, dr));
-            }
-
-            if let Some((deleteme, mut dl, dr)) = r.maybe_explode(depth + 1) {
-                if dl > 0 && l.explode_add_right(dl) {
-                    dl = 0;
-                }
-                if deleteme {
-                    *r = Box::new(Number::Literal(0));
-                }
-                return Some((false, dl, dr));
-            }
-        }
-
-        None
-    }
-
-    fn explode_add_left(&mut self, d: usize) -> bool {
-        match self {
-            Number::Literal(n) => {
-                *n += d;
-                true
-            }
-            Number::Pair(l, _) => l.explode_add_left(d),
-        }
-    }
-
-    fn explode_add_right(&mut self, d: usize) -> bool {
-        match self {
-            Number::Literal(n) => {
-                *n += d;
-                true
-            }
-            Number::Pair(_, r) => r.explode_add_right(d),
-        }
-    }
-
-    fn split(&mut self) -> bool {
-        match self {
-            Number::Literal(n) => {
-                if *n >= 10 {
-                    *self = Number::Pair(
-                        Box::new(Number::Literal(*n / 2)),
-                        Box::new(Number::Literal(n.div_ceil(2))),
-                    );
-                    true
-                } else {
-                    false
+// snailfish numbers are pairs all the way down, and the homework ones are already reduced so
+// none of them are nested inside four pairs
+fn parse_input(input: &str) -> anyhow::Result<Vec<Nested>> {
+    input
+        .lines()
+        .map(|line| {
+            let n: Nested = line.parse()?;
+            for (depth, node) in n.walk() {
+                if let Some(items) = node.as_list() {
+                    anyhow::ensure!(items.len() == 2, "'{node}' in '{line}' isn't a pair");
+                    anyhow::ensure!(depth < 4, "'{node}' in '{line}' needs exploding");
                 }
             }
-            // only leftmost number splits
-            Number::Pair(l, r) => l.split() || r.split(),
-        }
-    }
+            Ok(n)
+        })
+        .collect()
 }
 
-impl Display for Number {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Number::Literal(n) => write!(f, "{}", n),
-            Number::Pair(l, r) => {
-                write!(f, "[")?;
-                write!(f, "{}", l)?;
-                write!(f, ",")?;
-                write!(f, "{}", r)?;
-                write!(f, "]")
-            }
-        }
-    }
+fn add(l: Nested, r: Nested) -> Nested {
+    let mut n = Nested::List(vec![l, r]);
+    reduce(&mut n);
+    n
 }
 
-impl FromStr for Number {
-    type Err = anyhow::Error;
+fn magnitude(n: &Nested) -> i64 {
+    n.fold(&|n| n, &|items| 3 * items[0] + 2 * items[1])
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(parse_number(s))
-    }
+fn reduce(n: &mut Nested) {
+    while explode(n) || split(n) {}
 }
 
-fn parse_number(s: &str) -> Number {
-    let (_, n) = parse_number_impl(s);
-    n
+// explodes the leftmost pair nested inside four pairs
+fn explode(n: &mut Nested) -> bool {
+    let Some(path) = n.find(|path, node| path.len() == 4 && node.as_list().is_some()) else {
+        return false;
+    };
+    let mut cursor = n.cursor(path.clone()).unwrap();
+    let Nested::List(pair) = cursor.replace(Nested::Int(0)) else {
+        unreachable!();
+    };
+    let [Nested::Int(l), Nested::Int(r)] = pair[..] else {
+        unreachable!("unexpected structure at depth 4: {pair:?}");
+    };
+    if cursor.prev_int()
+        && let Nested::Int(n) = cursor.focus_mut()
+    {
+        *n += l;
+    }
+    let mut cursor = n.cursor(path).unwrap();
+    if cursor.next_int()
+        && let Nested::Int(n) = cursor.focus_mut()
+    {
+        *n += r;
+    }
+    true
 }
 
-fn parse_number_impl(s: &str) -> (usize, Number) {
-    match s.chars().next().unwrap() {
-        '[' => {
-            let mut s = &s[1..]; // skip [
-            let (skipl, left) = parse_number_impl(s);
-            s = &s[skipl + 1..]; // skip left Number + ,
-            let (skipr, right) = parse_number_impl(s);
-            (
-                3 + skipl + skipr, // 3 for [,]
-                Number::Pair(Box::new(left), Box::new(right)),
-            )
-        }
-        '0'..='9' => {
-            let end = s.find([',', ']']).unwrap();
-            let n: usize = s[..end].parse().unwrap();
-            (end, Number::Literal(n))
-        }
-        ch => unreachable!("got unexpected char {ch}"),
-    }
+// splits the leftmost number that's 10 or more
+fn split(n: &mut Nested) -> bool {
+    n.rewrite_first(|_, node| match *node {
+        Nested::Int(n) if n >= 10 => Some(Nested::List(vec![
+            Nested::Int(n / 2),
+            Nested::Int((n + 1) / 2),
+        ])),
+        _ => None,
+    })
 }
 
 #[cfg(test)]
@@ -208,6 +98,33 @@ mod tests {
     use rstest::rstest;
 
     const INPUT: &str = include_str!("../inputs/day18.input.txt");
+    const HOMEWORK: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    #[test]
+    fn test_homework() {
+        assert_eq!(4140, part01(HOMEWORK).unwrap());
+        assert_eq!(3993, part02(HOMEWORK).unwrap());
+    }
+
+    #[rstest]
+    #[case("[1]", "'[1]' in '[1]' isn't a pair")]
+    #[case("[[1,2],[1,2,3]]", "'[1,2,3]' in '[[1,2],[1,2,3]]' isn't a pair")]
+    #[case(
+        "[[[[[9,8],1],2],3],4]",
+        "'[9,8]' in '[[[[[9,8],1],2],3],4]' needs exploding"
+    )]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, parse_input(input).unwrap_err().to_string());
+    }
 
     #[test]
     fn test_part_one() {
@@ -231,9 +148,8 @@ mod tests {
     )]
     #[case("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]")]
     fn test_explode(#[case] s: &str, #[case] expected: &str) {
-        let mut number = parse_number(s);
-        let res = number.maybe_explode(0);
-        dbg!(res);
+        let mut number: Nested = s.parse().unwrap();
+        assert!(explode(&mut number));
         assert_eq!(expected, number.to_string());
     }
 
@@ -243,17 +159,16 @@ mod tests {
     #[case("[11,10]", "[[5,6],10]")]
     #[case("[[1,[2,20]],1]", "[[1,[2,[10,10]]],1]")]
     fn test_split(#[case] s: &str, #[case] expected: &str) {
-        let mut number = parse_number(s);
-        number.split();
+        let mut number: Nested = s.parse().unwrap();
+        split(&mut number);
         assert_eq!(expected, number.to_string());
     }
 
     #[test]
     fn test_reduce() {
-        let left = parse_number("[[[[4,3],4],4],[7,[[8,4],9]]]");
-        let right = parse_number("[1,1]");
-        let mut num = left.add(right);
-        num.reduce();
+        let left = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+        let right = "[1,1]".parse().unwrap();
+        let num = add(left, right);
         assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", num.to_string());
     }
 
@@ -267,8 +182,8 @@ mod tests {
     #[case("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791)]
     #[case("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137)]
     #[case("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]", 3488)]
-    fn test_magnitude(#[case] number: &str, #[case] expected: usize) {
-        let number = parse_number(number);
-        assert_eq!(expected, number.magnitude());
+    fn test_magnitude(#[case] number: &str, #[case] expected: i64) {
+        let number = number.parse().unwrap();
+        assert_eq!(expected, magnitude(&number));
     }
 }
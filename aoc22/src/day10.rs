@@ -1,4 +1,6 @@
 use anyhow::Context;
+use common::asm::{self, Flow, Machine};
+use common::ocr;
 use std::fmt::Write;
 use std::str::FromStr;
 
@@ -6,33 +8,41 @@ use crate::SolveInfo;
 
 pub fn run(input: &str, _: bool) -> anyhow::Result<SolveInfo> {
     Ok(SolveInfo {
-        part01: part01(input).to_string(),
-        part02: "\n".to_string() + &part02(input)?,
+        part01: part01(input)?.to_string(),
+        part02: part02(input)?,
     })
 }
 
-pub fn part01(input: &str) -> i32 {
+pub fn part01(input: &str) -> anyhow::Result<i32> {
+    let mut cpu = Machine::<Instruction>::parse(input, 1)?;
     let mut sum = 0;
-    let mut crt = Crt::new(|cycle, x| {
+    cpu.run_with(|cycle, x| {
         if cycle % 40 == 20 {
             sum += cycle as i32 * x;
         }
     });
-    input
-        .lines()
-        .flat_map(Instruction::from_str)
-        .for_each(|i| crt.process(i));
-    sum
+    Ok(sum)
 }
 
 pub fn part02(input: &str) -> anyhow::Result<String> {
-    let mut crt = Crt::new(|_, _| ());
-    input
-        .lines()
-        .flat_map(Instruction::from_str)
-        .for_each(|i| crt.process(i));
+    Ok(ocr::read_or_art(&render(input)?))
+}
+
+// what the CRT shows, one line per row
+fn render(input: &str) -> anyhow::Result<String> {
+    let mut cpu = Machine::<Instruction>::parse(input, 1)?;
+    let mut screen = [0u8; W * H];
+    cpu.run_with(|cycle, x| {
+        // the sprite is 3 pixels wide and centred on x
+        let pixel = cycle - 1;
+        if (x - 1..=x + 1).contains(&((pixel % W) as i32))
+            && let Some(p) = screen.get_mut(pixel)
+        {
+            *p = 1;
+        }
+    });
     let mut s = String::new();
-    for (i, pixel) in crt.screen.iter().enumerate() {
+    for (i, pixel) in screen.iter().enumerate() {
         if i > 0 && i % W == 0 {
             writeln!(&mut s)?;
         }
@@ -44,53 +54,7 @@ pub fn part02(input: &str) -> anyhow::Result<String> {
 const W: usize = 40;
 const H: usize = 6;
 
-struct Crt<F>
-where
-    F: FnMut(usize, i32),
-{
-    cycle: usize,
-    x: i32,
-    probe: F,
-    screen: [u8; W * H],
-}
-
-impl<F> Crt<F>
-where
-    F: FnMut(usize, i32),
-{
-    fn new(probe: F) -> Self {
-        Self {
-            cycle: 0,
-            x: 1,
-            probe,
-            screen: [0; W * H],
-        }
-    }
-
-    fn process(&mut self, i: Instruction) {
-        use Instruction::*;
-        match i {
-            Noop => {
-                self.tick();
-            }
-            AddX(v) => {
-                self.tick();
-                self.tick();
-                self.x += v;
-            }
-        }
-    }
-
-    fn tick(&mut self) {
-        let w = self.cycle % W;
-        if (self.x - 1..=self.x + 1).contains(&(w as i32)) {
-            self.screen[self.cycle] = 1;
-        }
-        self.cycle += 1;
-        (self.probe)(self.cycle, self.x);
-    }
-}
-
+#[derive(Debug)]
 enum Instruction {
     Noop,
     AddX(i32),
@@ -106,7 +70,30 @@ impl FromStr for Instruction {
             "addx" => Ok(Instruction::AddX(
                 words.next().context("no value for addx")?.parse()?,
             )),
-            i => unreachable!("unexpected instruction {}", i),
+            i => anyhow::bail!("unexpected instruction {}", i),
+        }
+    }
+}
+
+impl asm::Instruction for Instruction {
+    // the x register
+    type State = i32;
+
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        line.parse()
+    }
+
+    fn execute(&self, x: &mut i32) -> Flow {
+        if let Instruction::AddX(v) = self {
+            *x += v;
+        }
+        Flow::Next
+    }
+
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::AddX(_) => 2,
         }
     }
 }
@@ -120,13 +107,13 @@ mod tests {
 
     #[test]
     fn test_part_one_sample() {
-        let ans = part01(SAMPLE);
+        let ans = part01(SAMPLE).unwrap();
         assert_eq!(13140, ans);
     }
 
     #[test]
     fn test_part_one() {
-        let ans = part01(INPUT);
+        let ans = part01(INPUT).unwrap();
         assert_eq!(13180, ans);
     }
 
@@ -141,22 +128,15 @@ mod tests {
 #######.......#######.......#######.....
 "
         .trim();
-        let ans = part02(SAMPLE).unwrap();
+        let ans = render(SAMPLE).unwrap();
         assert_eq!(expect, ans);
+        // not letters, so the answer is the picture
+        assert_eq!(format!("\n{expect}"), part02(SAMPLE).unwrap());
     }
 
     #[test]
     fn test_part_two() {
-        let expect: &str = "
-####.####.####..##..#..#...##..##..###..
-#.......#.#....#..#.#..#....#.#..#.#..#.
-###....#..###..#....####....#.#..#.###..
-#.....#...#....#....#..#....#.####.#..#.
-#....#....#....#..#.#..#.#..#.#..#.#..#.
-####.####.#.....##..#..#..##..#..#.###..
-"
-        .trim();
         let ans = part02(INPUT).unwrap();
-        assert_eq!(expect, ans);
+        assert_eq!("EZFCHJAB", ans);
     }
 }
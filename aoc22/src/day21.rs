@@ -1,10 +1,11 @@
 use anyhow::Context;
-use itertools::process_results;
-use std::{
-    collections::{HashMap, VecDeque},
-    fmt::Display,
-    str::FromStr,
+use common::{
+    dot::{Dot, ToDot},
+    netlist::{Gate, Netlist},
+    symbolic::{Expr, solve},
 };
+use itertools::process_results;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use crate::SolveInfo;
 
@@ -16,161 +17,59 @@ pub fn run(input: &str, _: bool) -> anyhow::Result<SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<isize> {
-    let monkeys: Vec<Monkey> =
-        process_results(input.lines().map(Monkey::try_from), |it| it.collect())?;
+    monkeys(input)?.get("root")
+}
 
-    let monkeys_lookup: HashMap<String, Monkey> = monkeys
-        .into_iter()
-        .map(|monkey| (monkey.name.clone(), monkey))
-        .collect();
+// Draws who listens to whom, labelled with each monkey's job.
+pub fn dot(input: &str) -> anyhow::Result<Dot> {
+    Ok(monkeys(input)?.to_dot())
+}
 
-    let results = evaluate_graph(monkeys_lookup)?;
-    Ok(*results.get("root").unwrap())
+fn monkeys(input: &str) -> anyhow::Result<Netlist<Job>> {
+    process_results(input.lines().map(Monkey::try_from), |it| {
+        it.map(|monkey| (monkey.name, monkey.job)).collect()
+    })
 }
 
+// root checks that its two inputs are equal, so we build both sides as expressions in terms of
+// humn and solve for it
 pub fn part02(input: &str) -> anyhow::Result<isize> {
-    let monkeys: Vec<Monkey> =
-        process_results(input.lines().map(Monkey::try_from), |it| it.collect())?;
-    let mut monkeys_lookup: HashMap<String, Monkey> = monkeys
-        .into_iter()
-        .map(|monkey| (monkey.name.clone(), monkey))
-        .collect();
-
-    // find the path from "root" to "humn"
-    let path_to_humn = path_to_node(&monkeys_lookup, "humn")?;
-
-    // we rewrite the graph from "humn" to "root" one node at a time so that "humn" is now the node
-    // that needs to be solved for
-    let mut current_monkey = "humn".to_string();
-    for parent_name in path_to_humn.iter().rev() {
-        let parent = monkeys_lookup.get(parent_name).unwrap().clone();
-        monkeys_lookup
-            .entry(current_monkey.clone())
-            .and_modify(|m| {
-                if let Job::Operation(lhs, op, rhs) = &parent.job {
-                    // special case: root's new operation becomes "=" so we handle that here
-                    if parent_name == "root" {
-                        let other = if lhs == &current_monkey { rhs } else { lhs };
-                        m.job = Job::Lookup(other.to_string());
-                        return;
-                    }
-                    // division and subtraction are not commutative so they need to be handled
-                    // differently
-                    //
-                    // Scenarios:
-                    //   3
-                    // ? / 4   lhs = parent * rhs
-                    //
-                    //    3    (non-commutative)
-                    // 12 / ?  rhs = lhs / parent
-                    //
-                    //   3
-                    // ? - 4   lhs = parent + rhs
-                    //
-                    //   3     (non-commutative)
-                    // 7 - ?   rhs = lhs - parent
-                    //
-                    //   3
-                    // ? + 2   lhs = parent - rhs
-                    //
-                    //   3
-                    // 1 + ?   rhs = parent - lhs
-                    //
-                    //   8
-                    // ? * 4   lhs = parent / rhs
-                    //
-                    //   8
-                    // 2 * ?   rhs = parent / lhs
-                    if &current_monkey == rhs && (*op == Op::Div || *op == Op::Sub) {
-                        m.job = Job::Operation(lhs.to_string(), *op, parent_name.to_string());
-                    } else {
-                        let rhs = if lhs == &current_monkey { rhs } else { lhs };
-                        m.job =
-                            Job::Operation(parent_name.to_string(), op.invert(), rhs.to_string());
-                    }
-                } else {
-                    // just double-checking that every node along the path should be an equation
-                    // node
-                    panic!("monkey in path to humn is static: {parent:?}");
-                }
-            });
-        current_monkey = parent.name.clone();
-    }
-    // we don't need root anymore and instead of needing to rewrite it we just discard it
-    monkeys_lookup.remove("root");
-
-    let results = evaluate_graph(monkeys_lookup)?;
-    Ok(*results.get("humn").unwrap())
+    let monkeys: HashMap<String, Monkey> =
+        process_results(input.lines().map(Monkey::try_from), |it| {
+            it.map(|monkey| (monkey.name.clone(), monkey)).collect()
+        })?;
+
+    let Job::Operation(lhs, _, rhs) = &monkeys.get("root").context("no root monkey")?.job else {
+        anyhow::bail!("root monkey has no operation");
+    };
+    let lhs = expression(&monkeys, lhs)?.simplify();
+    let rhs = expression(&monkeys, rhs)?.simplify();
+    let humn = solve(&lhs, &rhs, "humn")?;
+    let humn = humn
+        .to_integer()
+        .with_context(|| format!("humn has to yell a fraction: {humn}"))?;
+    Ok(humn as isize)
 }
 
-// evaluate_graph evaluates all node in the graph in topological sorted order, the values for each
-// node are added to scope
-fn evaluate_graph(monkeys: HashMap<String, Monkey>) -> anyhow::Result<HashMap<String, isize>> {
-    let mut queue = VecDeque::new();
-    let mut in_degree = HashMap::new();
-    let mut inedges: HashMap<&String, Vec<&String>> = HashMap::new();
-    for (_, monkey) in monkeys.iter() {
-        let depends_on = monkey.depends_on();
-
-        match depends_on {
-            Some(deps) => {
-                for dep in deps {
-                    inedges.entry(dep).or_default().push(&monkey.name);
-                    in_degree
-                        .entry(&monkey.name)
-                        .and_modify(|cnt| *cnt += 1)
-                        .or_insert(1);
-                }
-            }
-            None => {
-                queue.push_back(&monkey.name);
-            }
-        }
+fn expression(monkeys: &HashMap<String, Monkey>, name: &str) -> anyhow::Result<Expr> {
+    if name == "humn" {
+        return Ok(Expr::var(name));
     }
-
-    let mut scope = HashMap::new();
-    while let Some(monkey_name) = queue.pop_front() {
-        let monkey = monkeys.get(monkey_name).context("monkey not found")?;
-
-        let value = monkey.eval(&scope)?;
-        scope.insert(monkey.name.clone(), value);
-
-        // update in degree for all connected verticies
-        if let Some(inedges) = inedges.get(&monkey.name) {
-            inedges.iter().for_each(|v| {
-                in_degree.entry(v).and_modify(|cnt| *cnt -= 1);
-                if in_degree[v] == 0 {
-                    queue.push_back(v);
-                }
-            });
-        }
-    }
-    Ok(scope)
-}
-
-fn path_to_node(monkeys: &HashMap<String, Monkey>, node: &str) -> anyhow::Result<Vec<String>> {
-    let mut path_to_node = vec![];
-
-    let mut stk = Vec::new();
-    let root = monkeys.get(&"root".to_string()).unwrap().clone();
-    let root_deps = root.depends_on().unwrap();
-    stk.push((root_deps[0].clone(), vec!["root".to_string()]));
-    stk.push((root_deps[1].clone(), vec!["root".to_string()]));
-    while let Some((monkey_name, path)) = stk.pop() {
-        if monkey_name == node {
-            path_to_node = path;
-            break;
-        }
-
-        let monkey = monkeys.get(&monkey_name).context("monkey not found")?;
-        if let Some(deps) = monkey.depends_on() {
-            let mut path = path.clone();
-            path.push(monkey_name);
-            stk.push((deps[0].clone(), path.clone()));
-            stk.push((deps[1].clone(), path.clone()));
+    let monkey = monkeys
+        .get(name)
+        .with_context(|| format!("monkey '{name}' not found"))?;
+    Ok(match &monkey.job {
+        Job::Number(n) => Expr::num(*n as i128),
+        Job::Operation(lhs, op, rhs) => {
+            let (lhs, rhs) = (expression(monkeys, lhs)?, expression(monkeys, rhs)?);
+            match op {
+                Op::Add => lhs + rhs,
+                Op::Sub => lhs - rhs,
+                Op::Mul => lhs * rhs,
+                Op::Div => lhs / rhs,
+            }
         }
-    }
-    Ok(path_to_node)
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -179,50 +78,6 @@ struct Monkey {
     job: Job,
 }
 
-impl Monkey {
-    fn depends_on(&self) -> Option<Vec<&String>> {
-        match &self.job {
-            Job::Number(_) => None,
-            Job::Lookup(other) => Some(vec![other]),
-            Job::Operation(lhs, _, rhs) => Some(vec![lhs, rhs]),
-        }
-    }
-
-    fn eval(&self, scope: &HashMap<String, isize>) -> anyhow::Result<isize> {
-        match &self.job {
-            Job::Number(n) => Ok(*n),
-            Job::Lookup(other) => Ok(*scope.get(other).context("not found")?),
-            Job::Operation(lhs, op, rhs) => {
-                let lhsv = scope
-                    .get(lhs)
-                    .context(format!("{}: {} not found in scope", self.name, lhs))?;
-                let rhsv = scope
-                    .get(rhs)
-                    .context(format!("{}: {} not found in scope", self.name, rhs))?;
-
-                Ok(match op {
-                    Op::Add => lhsv + rhsv,
-                    Op::Sub => lhsv - rhsv,
-                    Op::Mul => lhsv * rhsv,
-                    Op::Div => {
-                        // verify that we don't have any bugs due to expecting floats
-                        assert!(
-                            lhsv % rhsv == 0,
-                            "monkey={} lhs={} rhs={} lhsv={} rhsv={}",
-                            self.name,
-                            lhs,
-                            rhs,
-                            lhsv,
-                            rhsv
-                        );
-                        lhsv / rhsv
-                    }
-                })
-            }
-        }
-    }
-}
-
 impl TryFrom<&str> for Monkey {
     type Error = anyhow::Error;
 
@@ -249,7 +104,6 @@ impl TryFrom<&str> for Monkey {
 #[derive(Debug, Clone)]
 enum Job {
     Number(isize),
-    Lookup(String),
     Operation(String, Op, String),
 }
 
@@ -261,13 +115,42 @@ enum Op {
     Div,
 }
 
-impl Op {
-    fn invert(&self) -> Op {
+impl Gate for Job {
+    type Value = isize;
+
+    fn inputs(&self) -> Vec<&str> {
+        match self {
+            Job::Number(_) => vec![],
+            Job::Operation(lhs, _, rhs) => vec![lhs, rhs],
+        }
+    }
+
+    fn eval(&self, input: &dyn Fn(&str) -> isize) -> isize {
+        match self {
+            Job::Number(n) => *n,
+            Job::Operation(lhs, op, rhs) => {
+                let (lhsv, rhsv) = (input(lhs), input(rhs));
+                match op {
+                    Op::Add => lhsv + rhsv,
+                    Op::Sub => lhsv - rhsv,
+                    Op::Mul => lhsv * rhsv,
+                    Op::Div => {
+                        // verify that we don't have any bugs due to expecting floats
+                        assert!(
+                            lhsv % rhsv == 0,
+                            "lhs={lhs} rhs={rhs} lhsv={lhsv} rhsv={rhsv}"
+                        );
+                        lhsv / rhsv
+                    }
+                }
+            }
+        }
+    }
+
+    fn dot_attrs(&self) -> Vec<(&'static str, String)> {
         match self {
-            Op::Add => Op::Sub,
-            Op::Sub => Op::Add,
-            Op::Mul => Op::Div,
-            Op::Div => Op::Mul,
+            Job::Number(n) => vec![("xlabel", n.to_string())],
+            Job::Operation(_, op, _) => vec![("xlabel", op.to_string())],
         }
     }
 }
@@ -732,9 +732,9 @@ mod tests {
     }
 
     fn print_grid(data: &[Vec<Tile>]) {
-        for r in 0..data.len() {
-            for c in 0..data[0].len() {
-                print!("{}", data[r][c]);
+        for row in data {
+            for tile in row {
+                print!("{}", tile);
             }
             println!();
         }
@@ -2,6 +2,7 @@ use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use clap::Parser;
+use common::dot;
 
 use aoc22::*;
 
@@ -10,40 +11,48 @@ struct Cli {
     day: Option<usize>,
     #[arg(long)]
     sample: bool,
+    /// Print the day's input as a graphviz graph instead of solving it
+    #[arg(long)]
+    dot: bool,
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let days = [
-        day01::run,
-        day02::run,
-        day03::run,
-        day04::run,
-        day05::run,
-        day06::run,
-        day07::run,
-        day08::run,
-        day09::run,
-        day10::run,
-        day11::run,
-        day12::run,
-        day13::run,
-        day14::run,
-        day15::run,
-        day16::run,
-        day17::run,
-        day18::run,
-        day19::run,
-        day20::run,
-        day21::run,
-        day22::run,
-        day23::run,
-        day24::run,
-        day25::run,
+    let (days, graphs) = common::days![
+        day01,
+        day02,
+        day03,
+        day04,
+        day05,
+        day06,
+        day07,
+        day08,
+        day09,
+        day10,
+        day11,
+        day12,
+        day13,
+        day14,
+        day15,
+        day16,
+        day17,
+        day18,
+        day19,
+        day20,
+        day21(dot),
+        day22,
+        day23,
+        day24,
+        day25,
         // GENERATE DAY FUNCTION
     ];
 
+    if cli.dot {
+        let day = cli.day.context("--dot needs a day")?;
+        return dot::print_graph(&graphs, day, &day_input(day, cli.sample)?);
+    }
+
     if let Some(day) = cli.day {
         if day > days.len() {
             anyhow::bail!("Day {} not yet solved!", day)
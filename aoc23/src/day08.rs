@@ -1,5 +1,6 @@
+use anyhow::Context;
+use common::numtheory::{Cycle, lcm_of_cycles};
 use fxhash::FxHashMap;
-use num::integer::Integer;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -16,12 +17,24 @@ pub fn part01(input: &str) -> anyhow::Result<u64> {
 pub fn part02(input: &str) -> anyhow::Result<u64> {
     let (moves, nodes) = parse_input(input);
 
-    Ok(nodes
+    let cycles: Vec<Cycle> = nodes
         .keys()
         .filter(|n| n.ends_with('A'))
-        .cloned()
-        .map(|n| navigate(moves, &nodes, n, |node| node.ends_with('Z')))
-        .fold(1u64, |acc, n| acc.lcm(&n)))
+        .map(|&start| {
+            // walk past the first end node to find out how long it takes to come back around
+            let first = navigate(moves, &nodes, start, |node| node.ends_with('Z'));
+            let mut seen_end = false;
+            let second = navigate(moves, &nodes, start, |node| {
+                let is_end = node.ends_with('Z');
+                let done = is_end && seen_end;
+                seen_end |= is_end;
+                done
+            });
+            Cycle::from_hits(first, second)
+        })
+        .collect();
+
+    lcm_of_cycles(&cycles).context("cycles to the end nodes do not start at offset zero")
 }
 
 fn parse_input(input: &str) -> (&str, FxHashMap<&str, (&str, &str)>) {
@@ -36,9 +49,14 @@ fn parse_input(input: &str) -> (&str, FxHashMap<&str, (&str, &str)>) {
     (moves, nodes)
 }
 
-fn navigate<F>(moves: &str, nodes: &FxHashMap<&str, (&str, &str)>, start: &str, is_end: F) -> u64
+fn navigate<F>(
+    moves: &str,
+    nodes: &FxHashMap<&str, (&str, &str)>,
+    start: &str,
+    mut is_end: F,
+) -> u64
 where
-    F: Fn(&str) -> bool,
+    F: FnMut(&str) -> bool,
 {
     let mut steps = 0;
     let mut current = start;
@@ -66,6 +84,23 @@ mod tests {
         assert_eq!(20777, ans);
     }
 
+    #[test]
+    fn test_part_two_sample() {
+        let sample = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        let ans = part02(sample).unwrap();
+        assert_eq!(6, ans);
+    }
+
     #[test]
     fn test_part_two() {
         let ans = part02(INPUT).unwrap();
@@ -1,6 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
-use itertools::iproduct;
+use anyhow::Context;
+use common::geometry::{self, Point};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -19,305 +20,96 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 // S is the starting position of the animal; there is a pipe on this tile, but your sketch doesn't show what shape the pipe has.
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let mut grid: HashMap<(isize, isize), char> = HashMap::new();
-    for (r, line) in input.lines().enumerate() {
-        for (c, char) in line.char_indices() {
-            grid.insert((r as isize, c as isize), char);
-        }
-    }
-
-    let start = grid
-        .iter()
-        .find(|(_, ch)| **ch == 'S')
-        .map(|(pos, _)| pos)
-        .unwrap();
-
-    let mut seen = HashSet::new();
-    let mut stack = Vec::new();
-    stack.push((start.0, start.1, vec![]));
-    let len = loop {
-        let current = stack.pop().unwrap();
-        let (row, col, path) = current;
-        let ch = grid[&(row, col)];
-
-        if grid[&(row, col)] == 'S' && path.len() > 1 && path[1] != *path.last().unwrap() {
-            break path.len();
-        }
-
-        if !seen.insert((row, col)) {
-            continue;
-        }
-
-        // south
-        if ch == 'S' || ch == '|' || ch == '7' || ch == 'F' {
-            match grid.get(&(row + 1, col)) {
-                Some('|') | Some('L') | Some('J') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row + 1, col, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-
-        // north
-        if ch == 'S' || ch == '|' || ch == 'L' || ch == 'J' {
-            match grid.get(&(row - 1, col)) {
-                Some('|') | Some('7') | Some('F') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row - 1, col, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-
-        // east
-        if ch == 'S' || ch == '-' || ch == 'L' || ch == 'F' {
-            match grid.get(&(row, col + 1)) {
-                Some('-') | Some('J') | Some('7') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row, col + 1, path));
-                }
-                Some(_) | None => (),
-            }
-        }
+    Ok(find_loop(input)?.len() / 2)
+}
 
-        // west
-        if ch == 'S' || ch == '-' || ch == 'J' || ch == '7' {
-            match grid.get(&(row, col - 1)) {
-                Some('-') | Some('L') | Some('F') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row, col - 1, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-    };
+pub fn part02(input: &str) -> anyhow::Result<u64> {
+    // the loop is a polygon with a vertex on every tile, so the enclosed tiles are its interior
+    // lattice points
+    let polygon: Vec<Point> = find_loop(input)?
+        .into_iter()
+        .map(|(row, col)| (row as i64, col as i64))
+        .collect();
+    Ok(geometry::interior_points(&polygon))
+}
 
-    let mid = len / 2;
-    Ok(mid)
+// the (row, col) directions a tile connects to
+fn connections(ch: char) -> &'static [(isize, isize)] {
+    match ch {
+        '|' => &[(-1, 0), (1, 0)],
+        '-' => &[(0, -1), (0, 1)],
+        'L' => &[(-1, 0), (0, 1)],
+        'J' => &[(-1, 0), (0, -1)],
+        '7' => &[(1, 0), (0, -1)],
+        'F' => &[(1, 0), (0, 1)],
+        'S' => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+        _ => &[],
+    }
 }
 
-pub fn part02(input: &str) -> anyhow::Result<usize> {
+// the tiles of the loop through S, in order, starting at S
+fn find_loop(input: &str) -> anyhow::Result<Vec<(isize, isize)>> {
     let mut grid: HashMap<(isize, isize), char> = HashMap::new();
-    let height = input.lines().count();
-    let width = input.lines().next().unwrap().len();
-    // println!("h={height} w={width}");
     for (r, line) in input.lines().enumerate() {
         for (c, char) in line.char_indices() {
             grid.insert((r as isize, c as isize), char);
         }
     }
 
-    let start = grid
+    let start = *grid
         .iter()
         .find(|(_, ch)| **ch == 'S')
         .map(|(pos, _)| pos)
-        .unwrap();
-
-    let mut seen = HashSet::new();
-    let mut stack = Vec::new();
-    stack.push((start.0, start.1, vec![]));
-    let path = loop {
-        let current = stack.pop().unwrap();
-        let (row, col, path) = current;
-        let ch = grid[&(row, col)];
-
-        if grid[&(row, col)] == 'S' && path.len() > 1 && path[1] != *path.last().unwrap() {
-            break path;
-        }
-
-        if !seen.insert((row, col)) {
-            continue;
-        }
-
-        // south
-        if ch == 'S' || ch == '|' || ch == '7' || ch == 'F' {
-            match grid.get(&(row + 1, col)) {
-                Some('|') | Some('L') | Some('J') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row + 1, col, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-
-        // north
-        if ch == 'S' || ch == '|' || ch == 'L' || ch == 'J' {
-            match grid.get(&(row - 1, col)) {
-                Some('|') | Some('7') | Some('F') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row - 1, col, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-
-        // east
-        if ch == 'S' || ch == '-' || ch == 'L' || ch == 'F' {
-            match grid.get(&(row, col + 1)) {
-                Some('-') | Some('J') | Some('7') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row, col + 1, path));
-                }
-                Some(_) | None => (),
-            }
-        }
+        .context("no start")?;
+    let step = |pos: (isize, isize), dir: (isize, isize)| (pos.0 + dir.0, pos.1 + dir.1);
+    let tile = |pos| grid.get(&pos).copied().unwrap_or('.');
 
-        // west
-        if ch == 'S' || ch == '-' || ch == 'J' || ch == '7' {
-            match grid.get(&(row, col - 1)) {
-                Some('-') | Some('L') | Some('F') | Some('S') => {
-                    let mut path = path.clone();
-                    path.push((row, col));
-                    stack.push((row, col - 1, path));
-                }
-                Some(_) | None => (),
-            }
-        }
-    };
-
-    clean(&mut grid, width as isize, height as isize, &path);
-    let scaled = scale(&grid, width as isize, height as isize);
-    print_grid(&scaled, (width * 3) as isize, (height * 3) as isize);
-
-    let mut inside = vec![];
-    for (row, col) in iproduct!(0..height, 0..width) {
-        let (row, col) = (row as isize, col as isize);
-        if path.contains(&(row, col)) {
-            continue;
-        }
-
-        if !is_outside(
-            &scaled,
-            &mut HashSet::new(),
-            (height * 3) as isize,
-            (width * 3) as isize,
-            (row * 3) + 1,
-            (col * 3) + 1,
-        ) {
-            inside.push((row, col));
-        }
-    }
-
-    Ok(inside.len())
-}
-
-fn clean(
-    grid: &mut HashMap<(isize, isize), char>,
-    width: isize,
-    height: isize,
-    path: &[(isize, isize)],
-) {
-    for (row, col) in iproduct!(0..height, 0..width) {
-        if !path.contains(&(row, col)) {
-            grid.insert((row, col), '.');
-        }
-    }
-}
-
-fn scale(
-    grid: &HashMap<(isize, isize), char>,
-    width: isize,
-    height: isize,
-) -> HashMap<(isize, isize), char> {
-    let mut s_pos = (0, 0);
-    let mut scaled = HashMap::new();
-    for (row, col) in iproduct!(0..height, 0..width) {
-        let mask = match grid[&(row, col)] {
-            '|' => ".x..x..x.",
-            '-' => "...xxx...",
-            'L' => ".x..xx...",
-            'J' => ".x.xx....",
-            '7' => "...xx..x.",
-            'F' => "....xx.x.",
-            // we fill in 'S' later
-            'S' => {
-                s_pos = ((row * 3) + 1, (col * 3) + 1);
-                "....x...."
-            }
-            _ => ".........",
-        };
-
-        for i in 0..9 {
-            let sr = row * 3 + (i / 3);
-            let sc = col * 3 + (i % 3);
-            scaled.insert((sr, sc), mask.chars().nth(i as usize).unwrap());
-        }
-    }
-
-    // populate the 'S'
-    for (dr, dc) in [(2, 0), (-2, 0), (0, 2), (0, -2)] {
-        if let Some('x') = scaled.get(&(s_pos.0 + dr, s_pos.1 + dc)) {
-            scaled.insert((s_pos.0 + (dr / 2), s_pos.1 + (dc / 2)), 'x');
-        }
-    }
-
-    scaled
-}
-
-fn grid_str(grid: &HashMap<(isize, isize), char>, width: isize, height: isize) -> String {
-    let mut s = String::new();
-    for row in 0..height {
-        for col in 0..width {
-            s.push(grid[&(row, col)]);
-        }
-        s.push('\n');
-    }
-    s.trim_end().to_string()
-}
-
-fn print_grid(grid: &HashMap<(isize, isize), char>, width: isize, height: isize) {
-    println!("{}", grid_str(grid, width, height));
-}
-
-fn is_outside(
-    grid: &HashMap<(isize, isize), char>,
-    // curr_path: Vec<(isize, isize)>,
-    seen: &mut HashSet<(isize, isize)>,
-    height: isize,
-    width: isize,
-    row: isize,
-    col: isize,
-) -> bool {
-    if !seen.insert((row, col)) {
-        return false;
-    }
-    // println!("{row},{col} path={curr_path:?}");
-    match grid.get(&(row, col)) {
-        Some('x') => {
-            // println!("  wall");
-            return false;
-        }
-        None => {
-            // println!("  off grid");
-            return false;
-        }
-        Some(_) => (),
+    // leave S towards any pipe that connects back to it, then follow the pipes round
+    let mut dir = *connections('S')
+        .iter()
+        .find(|&&(dr, dc)| connections(tile(step(start, (dr, dc)))).contains(&(-dr, -dc)))
+        .context("nothing connects to the start")?;
+    let mut path = vec![start];
+    let mut pos = step(start, dir);
+    while pos != start {
+        let back = (-dir.0, -dir.1);
+        let pipe = connections(tile(pos));
+        anyhow::ensure!(pipe.contains(&back), "the loop is broken at {pos:?}");
+        path.push(pos);
+        dir = *pipe.iter().find(|&&d| d != back).unwrap();
+        pos = step(pos, dir);
     }
-
-    row == 0
-        || row == height - 1
-        || col == 0
-        || col == width - 1
-        || is_outside(grid, seen, height, width, row + 1, col)
-        || is_outside(grid, seen, height, width, row - 1, col)
-        || is_outside(grid, seen, height, width, row, col + 1)
-        || is_outside(grid, seen, height, width, row, col - 1)
+    Ok(path)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     const INPUT: &str = include_str!("../inputs/day10.input.txt");
 
+    #[rstest]
+    #[case(".....\n.S-7.\n.|.|.\n.L-J.\n.....", 4, 1)]
+    #[case("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ...", 8, 1)]
+    #[case(
+        "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........",
+        23,
+        4
+    )]
+    fn test_samples(#[case] input: &str, #[case] one: usize, #[case] two: u64) {
+        assert_eq!(one, part01(input).unwrap());
+        assert_eq!(two, part02(input).unwrap());
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT).unwrap();
@@ -1,4 +1,4 @@
-use itertools::Itertools;
+use common::geometry;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -47,11 +47,8 @@ pub fn part02(input: &str) -> anyhow::Result<u64> {
 }
 
 fn solve(input: Vec<(&str, u64)>) -> u64 {
-    let points = calculate_points(&input);
-    let perimeter = input.iter().map(|(_, dist)| dist).sum::<u64>();
-    let inner_area = calculate_area(&points);
-    // pick's theorem to account for the "half" of the perimeter that is part of the area
-    inner_area + (perimeter / 2) + 1
+    // every point on or inside the trench gets dug out
+    geometry::lattice_points(&calculate_points(&input))
 }
 
 fn calculate_points(input: &[(&str, u64)]) -> Vec<(i64, i64)> {
@@ -71,21 +68,31 @@ fn calculate_points(input: &[(&str, u64)]) -> Vec<(i64, i64)> {
     points
 }
 
-// shoelace formula
-fn calculate_area(points: &[(i64, i64)]) -> u64 {
-    points
-        .iter()
-        .tuple_windows()
-        .fold(0, |acc, (a, b)| acc + (a.0 * b.1) - (a.1 * b.0))
-        .unsigned_abs()
-        / 2
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const INPUT: &str = include_str!("../inputs/day18.input.txt");
+    const SAMPLE: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(62, part01(SAMPLE).unwrap());
+        assert_eq!(952408144115, part02(SAMPLE).unwrap());
+    }
 
     #[test]
     fn test_part_one() {
@@ -2,6 +2,8 @@ use std::str::FromStr;
 
 use anyhow::Context;
 
+use common::hyperrect::HyperRect;
+
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
         part01: part01(input)?.to_string(),
@@ -34,19 +36,15 @@ pub fn part02(input: &str) -> anyhow::Result<u64> {
     let (workflows, _) = parse_input(input);
 
     let mut accepted = Vec::new();
-    find_accepted_ranges(&workflows, "in", &Ranges::default(), &mut accepted);
+    let ranges = HyperRect::new([1; 4], [4001; 4]);
+    find_accepted_ranges(&workflows, "in", &ranges, &mut accepted);
 
-    Ok(accepted
-        .into_iter()
-        .map(|r| {
-            (r.x.1 - r.x.0) as u64
-                * (r.m.1 - r.m.0) as u64
-                * (r.a.1 - r.a.0) as u64
-                * (r.s.1 - r.s.0) as u64
-        })
-        .sum())
+    Ok(accepted.into_iter().map(|r| r.volume()).sum())
 }
 
+// each category is one axis of a 4D box of ratings
+type Ranges = HyperRect<4>;
+
 fn find_accepted_ranges(
     workflows: &[Workflow],
     workflow: &str,
@@ -66,65 +64,38 @@ fn find_accepted_ranges(
         .with_context(|| format!("workflow {} not found", workflow))
         .unwrap();
 
-    let mut ranges = *ranges;
+    let mut ranges = Some(*ranges);
     for expr in &workflow.exprs {
+        let Some(current) = ranges else {
+            break;
+        };
         match expr {
             Expr::Gt(category, bound, next) => {
-                // expr is true
-                let mut gt_ranges = ranges;
-                gt_ranges.modify(category, |r| r.0 = r.0.max(*bound + 1));
-                find_accepted_ranges(workflows, next, &gt_ranges, accepted);
-
-                // expr is false
-                ranges.modify(category, |r| r.1 = r.1.min(*bound + 1));
+                let (falsy, truthy) = current.split(axis(category), *bound as i64 + 1);
+                if let Some(truthy) = truthy {
+                    find_accepted_ranges(workflows, next, &truthy, accepted);
+                }
+                ranges = falsy;
             }
             Expr::Lt(category, bound, next) => {
-                // expr is true
-                let mut lt_ranges = ranges;
-                lt_ranges.modify(category, |r| r.1 = r.1.min(*bound));
-                find_accepted_ranges(workflows, next, &lt_ranges, accepted);
-
-                // expr is false
-                ranges.modify(category, |r| r.0 = r.0.max(*bound));
+                let (truthy, falsy) = current.split(axis(category), *bound as i64);
+                if let Some(truthy) = truthy {
+                    find_accepted_ranges(workflows, next, &truthy, accepted);
+                }
+                ranges = falsy;
             }
-            Expr::Goto(next) => find_accepted_ranges(workflows, next, &ranges, accepted),
+            Expr::Goto(next) => find_accepted_ranges(workflows, next, &current, accepted),
         }
     }
 }
 
-// note: ranges are [min, max)
-#[derive(Debug, Clone, Copy)]
-struct Ranges {
-    x: (u32, u32),
-    m: (u32, u32),
-    a: (u32, u32),
-    s: (u32, u32),
-}
-
-impl Ranges {
-    fn modify<F>(&mut self, category: &str, f: F)
-    where
-        F: FnOnce(&mut (u32, u32)),
-    {
-        match category {
-            "x" => f(&mut self.x),
-            "m" => f(&mut self.m),
-            "a" => f(&mut self.a),
-            "s" => f(&mut self.s),
-            _ => unreachable!(),
-        }
-    }
-}
-
-impl Default for Ranges {
-    fn default() -> Self {
-        let r = (1, 4001);
-        Self {
-            x: r,
-            m: r,
-            a: r,
-            s: r,
-        }
+fn axis(category: &str) -> usize {
+    match category {
+        "x" => 0,
+        "m" => 1,
+        "a" => 2,
+        "s" => 3,
+        _ => unreachable!(),
     }
 }
 
@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use anyhow::Context;
-use num::Integer;
+use common::numtheory::{Cycle, lcm_of_cycles};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -96,23 +96,29 @@ pub fn part02(input: &str) -> anyhow::Result<u64> {
         }
     }
 
-    let mut lcm = 1;
+    let mut cycles = vec![];
     for conj in conjunctions {
         let mut modules = modules.clone();
-        let mut found = false;
+        // record the first two presses that send a low pulse to the conjunction so we can check
+        // it really is a cycle that the LCM will work for
+        let mut hits = vec![];
         let mut i = 0;
-        while !found {
+        while hits.len() < 2 {
             i += 1;
+            let mut found = false;
             simulate_button_press(&mut modules, |name, pulse, _| {
                 if name == conj && pulse == &Pulse::Low {
                     found = true;
                 }
             });
+            if found {
+                hits.push(i);
+            }
         }
-        lcm = lcm.lcm(&i);
+        cycles.push(Cycle::from_hits(hits[0], hits[1]));
     }
 
-    Ok(lcm)
+    lcm_of_cycles(&cycles).context("conjunction cycles do not start at offset zero")
 }
 
 fn simulate_button_press<F>(modules: &mut HashMap<String, Module>, mut inspect: F)
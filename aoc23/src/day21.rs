@@ -1,7 +1,7 @@
 use std::collections::{HashSet, VecDeque};
 
+use common::poly::extrapolate;
 use itertools::{Itertools, iproduct};
-use polyfit_rs::polyfit_rs::polyfit;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -32,20 +32,13 @@ pub fn part02(input: &str) -> anyhow::Result<u64> {
     let mut y_values = Vec::new();
     for n in [65, 196, 327] {
         let y = garden_plots_reached(&expanded, (size as i32 / 2, size as i32 / 2), n);
-        y_values.push(y as f64);
+        y_values.push(y as i128);
     }
     // target is the furthest point we are going to reach.
     // assumption is that we reach the edge of a grid (not somewhere in the middle).
     let target = (26501365 - 64) / grid.len();
-    let cos = polyfit(&[0f64, 1f64, 2f64], &y_values, 2).unwrap();
-    // calculate the polynomial value at the target
-    Ok(cos
-        .iter()
-        .map(|co| co.round())
-        .enumerate()
-        .fold(0, |acc, (i, co)| {
-            acc + (co * (target as f64).powi(i as i32)) as u64
-        }))
+    // the number of plots reached grows quadratically with the number of grids we walk through
+    Ok(extrapolate(&y_values, target as i128) as u64)
 }
 
 fn parse_input(input: &str) -> Vec<Vec<char>> {
@@ -9,7 +9,7 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
     let (mut bricks, size) = parse_input(input);
-    bricks.sort_by(|a, b| a.0.z.cmp(&b.0.z));
+    bricks.sort_by_key(|a| a.0.z);
 
     drop_bricks(&mut bricks, size);
 
@@ -27,7 +27,7 @@ pub fn part01(input: &str) -> anyhow::Result<usize> {
 
 pub fn part02(input: &str) -> anyhow::Result<usize> {
     let (mut bricks, size) = parse_input(input);
-    bricks.sort_by(|a, b| a.0.z.cmp(&b.0.z));
+    bricks.sort_by_key(|a| a.0.z);
 
     drop_bricks(&mut bricks, size);
 
@@ -1,6 +1,5 @@
-use std::collections::BinaryHeap;
-
-use fxhash::FxHashSet;
+use anyhow::Context;
+use common::maze::JunctionGraph;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -11,16 +10,10 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
     let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let start = (0, grid[0].iter().position(|&c| c == '.').unwrap());
-    let end = (
-        grid.len() - 1,
-        grid[grid.len() - 1].iter().position(|&c| c == '.').unwrap(),
-    );
-    Ok(longest_path(&grid, start, end))
+    longest_hike(&grid)
 }
 
 pub fn part02(input: &str) -> anyhow::Result<usize> {
-    println!("part02");
     let grid: Vec<Vec<char>> = input
         .lines()
         .map(|l| {
@@ -32,85 +25,35 @@ pub fn part02(input: &str) -> anyhow::Result<usize> {
                 .collect()
         })
         .collect();
+    longest_hike(&grid)
+}
+
+fn longest_hike(grid: &[Vec<char>]) -> anyhow::Result<usize> {
     let start = (0, grid[0].iter().position(|&c| c == '.').unwrap());
     let end = (
         grid.len() - 1,
         grid[grid.len() - 1].iter().position(|&c| c == '.').unwrap(),
     );
-    Ok(longest_path_dfs(
-        &grid,
-        &mut FxHashSet::default(),
-        start,
-        end,
-    ))
-}
-
-fn longest_path(grid: &[Vec<char>], start: (usize, usize), end: (usize, usize)) -> usize {
-    let mut queue = BinaryHeap::new();
-    queue.push((0, start, vec![]));
-
-    let mut distances = vec![vec![0; grid[0].len()]; grid.len()];
 
-    while let Some((dist, (row, col), path)) = queue.pop() {
-        distances[row][col] = distances[row][col].max(dist);
-        let deltas = match grid[row][col] {
-            '>' => vec![(0, 1)],
-            '<' => vec![(0, -1)],
-            'v' => vec![(1, 0)],
-            '^' => vec![(-1, 0)],
-            _ => vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
-        };
-
-        for (dr, dc) in deltas {
-            let (nr, nc) = (row as i32 + dr, col as i32 + dc);
-
-            if nr < 0 || nr >= grid.len() as i32 || nc < 0 || nc >= grid[0].len() as i32 {
-                continue;
-            }
-
-            let (nr, nc) = (nr as usize, nc as usize);
-            if grid[nr][nc] == '#' || path.contains(&(nr, nc)) {
-                continue;
-            }
-
-            let mut path = path.clone();
-            path.push((row, col));
-            queue.push((dist + 1, (nr, nc), path));
-        }
-    }
-    distances[end.0][end.1]
+    let graph = JunctionGraph::from_grid(&[start, end], |pos| moves(grid, pos));
+    let (start, end) = (graph.node(start).unwrap(), graph.node(end).unwrap());
+    graph
+        .longest_simple_path_par(start, end)
+        .context("no path to the end")
 }
 
-fn longest_path_dfs(
-    grid: &Vec<Vec<char>>,
-    visited: &mut FxHashSet<(usize, usize)>,
-    start: (usize, usize),
-    end: (usize, usize),
-) -> usize {
-    if start == end {
-        return visited.len();
-    }
-
-    let (row, col) = start;
-    let mut longest_path = 0;
-    for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-        let (nr, nc) = (row as i32 + dr, col as i32 + dc);
-        if nr < 0 || nr >= grid.len() as i32 || nc < 0 || nc >= grid[0].len() as i32 {
-            continue;
-        }
-
-        let (nr, nc) = (nr as usize, nc as usize);
-        if grid[nr][nc] == '#' {
-            continue;
-        }
-
-        if !visited.insert((nr, nc)) {
-            continue;
-        }
-        longest_path = longest_path.max(longest_path_dfs(grid, visited, (nr, nc), end));
-        visited.remove(&(nr, nc));
-    }
-    longest_path
+fn moves(grid: &[Vec<char>], (row, col): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let deltas: &[(isize, isize)] = match grid[row][col] {
+        '>' => &[(0, 1)],
+        '<' => &[(0, -1)],
+        'v' => &[(1, 0)],
+        '^' => &[(-1, 0)],
+        _ => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+    };
+    deltas.iter().filter_map(move |&(dr, dc)| {
+        let (nr, nc) = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+        (*grid.get(nr)?.get(nc)? != '#').then_some((nr, nc))
+    })
 }
 
 #[cfg(test)]
@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
 use anyhow::Context;
+use common::linalg::{LinearSystem, Matrix, Scalar};
 use itertools::Itertools;
+use num::BigRational;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -11,7 +13,10 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<u64> {
-    let hailstones = input.lines().flat_map(Hailstone::from_str).collect_vec();
+    let hailstones: Vec<Hailstone> = input
+        .lines()
+        .map(Hailstone::from_str)
+        .collect::<anyhow::Result<_>>()?;
     // let rect = Rect {
     //     tl: (7.0, 27.0),
     //     br: (27.0, 7.0),
@@ -34,8 +39,10 @@ pub fn part01(input: &str) -> anyhow::Result<u64> {
         // take a step in the direction of the velocity, if the distance to the intercept
         // increases, you are moving away from it (i.e. it is in the past)
         for h in [h1, h2] {
-            let d = (h.x - intercept_x).abs() + (h.y - intercept_y).abs();
-            let dn = ((h.x + h.vx) - intercept_x).abs() + ((h.y + h.vy) - intercept_y).abs();
+            let (x, y) = (h.p[0] as f64, h.p[1] as f64);
+            let (vx, vy) = (h.v[0] as f64, h.v[1] as f64);
+            let d = (x - intercept_x).abs() + (y - intercept_y).abs();
+            let dn = ((x + vx) - intercept_x).abs() + ((y + vy) - intercept_y).abs();
             if dn > d {
                 continue 'combinations;
             }
@@ -48,43 +55,83 @@ pub fn part01(input: &str) -> anyhow::Result<u64> {
     Ok(ans)
 }
 
-pub fn part02(input: &str) -> anyhow::Result<i64> {
-    let hailstones = input
+pub fn part02(input: &str) -> anyhow::Result<i128> {
+    let hailstones: Vec<Hailstone> = input
         .lines()
-        .flat_map(Hailstone::from_str)
-        // you only need 3 points to define a plane
-        .take(3)
-        .collect_vec();
-
-    // print a mathematica script
-    let mut equations = vec![];
-    for (t, h) in hailstones.iter().enumerate() {
-        equations.push(format!("t{t} >= 0"));
-        equations.push(format!(
-            "{x} + {vx} t{t} == rx + vx t{t}",
-            t = t,
-            x = h.x,
-            vx = h.vx,
-        ));
-        equations.push(format!(
-            "{y} + {vy} t{t} == ry + vy t{t}",
-            t = t,
-            y = h.y,
-            vy = h.vy,
-        ));
-        equations.push(format!(
-            "{z} + {vz} t{t} == rz + vz t{t}",
-            t = t,
-            z = h.z,
-            vz = h.vz,
-        ));
+        .map(Hailstone::from_str)
+        .collect::<anyhow::Result<_>>()?;
+
+    // any three hailstones are enough to pin down the rock, but some combinations don't give an
+    // independent set of equations (e.g. parallel velocities) so keep trying until one does
+    let rock = hailstones
+        .iter()
+        .tuple_combinations()
+        .find_map(|(h0, h1, h2)| solve_rock(h0, h1, h2))
+        .context("no hailstones give a unique rock trajectory")?;
+
+    if let Some(missed) = hailstones.iter().find(|h| !rock.collides_with(h)) {
+        anyhow::bail!("rock {:?} never hits hailstone {:?}", rock, missed);
+    }
+
+    Ok(rock.p.iter().sum())
+}
+
+// The rock (P, V) collides with hailstone i (p_i, v_i) at some time t, so P - p_i and V - v_i are
+// parallel which means
+//
+//   (P - p_i) x (V - v_i) = 0
+//   P x V - P x v_i - p_i x V + p_i x v_i = 0
+//
+// The P x V term is non-linear, but it is the same for every hailstone so subtracting the equation
+// for hailstone j from the one for hailstone i cancels it out:
+//
+//   P x (v_i - v_j) + (p_i - p_j) x V = p_i x v_i - p_j x v_j
+//
+// That is three linear equations in the six unknowns, so two pairs of hailstones are enough.
+fn solve_rock(h0: &Hailstone, h1: &Hailstone, h2: &Hailstone) -> Option<Hailstone> {
+    let mut rows = Vec::new();
+    for (hi, hj) in [(h0, h1), (h0, h2)] {
+        let w = sub(hi.v, hj.v);
+        let q = sub(hi.p, hj.p);
+        let c = sub(cross(hi.p, hi.v), cross(hj.p, hj.v));
+        // columns are [Px, Py, Pz, Vx, Vy, Vz, rhs]
+        rows.push([0, w[2], -w[1], 0, -q[2], q[1], c[0]]);
+        rows.push([-w[2], 0, w[0], q[2], 0, -q[0], c[1]]);
+        rows.push([w[1], -w[0], 0, -q[1], q[0], 0, c[2]]);
     }
-    println!(
-        "Solve[{{{}}}, {{rx, ry, rz, vx, vy, vz}}]",
-        equations.join(", ")
+
+    let augmented = Matrix::new(
+        rows.into_iter()
+            .map(|row| row.map(BigRational::from_i128).to_vec())
+            .collect(),
     );
+    let solution = LinearSystem::from_augmented(augmented).solve()?;
+    let solution: Vec<i128> = solution
+        .iter()
+        .map(|v| v.to_i128())
+        .collect::<Option<_>>()?;
+    Some(Hailstone {
+        p: [solution[0], solution[1], solution[2]],
+        v: [solution[3], solution[4], solution[5]],
+    })
+}
+
+type Vec3 = [i128; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
 
-    Ok(0)
+fn dot(a: Vec3, b: Vec3) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -101,21 +148,29 @@ impl Rect {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct Hailstone {
-    x: f64,
-    y: f64,
-    z: f64,
-    vx: f64,
-    vy: f64,
-    vz: f64,
+    p: Vec3,
+    v: Vec3,
 }
 
 impl Hailstone {
     fn m(&self) -> f64 {
-        self.vy / self.vx
+        self.v[1] as f64 / self.v[0] as f64
     }
 
     fn b(&self) -> f64 {
-        self.y - (self.m() * self.x)
+        self.p[1] as f64 - (self.m() * self.p[0] as f64)
+    }
+
+    // whether this (thrown as a rock) hits the other hailstone, now or in the future
+    fn collides_with(&self, other: &Hailstone) -> bool {
+        let dp = sub(self.p, other.p);
+        let dv = sub(self.v, other.v);
+        if dv == [0; 3] {
+            return dp == [0; 3];
+        }
+        // they meet at time t when dp = -dv * t, so dp must be parallel to dv and point the
+        // opposite way for t to be in the future
+        cross(dp, dv) == [0; 3] && dot(dp, dv) <= 0
     }
 }
 
@@ -124,60 +179,47 @@ impl FromStr for Hailstone {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (position, velocity) = s.split_once(" @ ").context("malformed")?;
-        let (x, y, z) = position
-            .split(", ")
-            .map(|s| s.parse().unwrap())
-            .collect_tuple()
-            .context("malformed")?;
-        let (vx, vy, vz) = velocity
-            .split(", ")
-            .map(|s| {
-                s.trim()
-                    .parse()
-                    .with_context(|| format!("parse failure '{s}'"))
-                    .unwrap()
-            })
-            .collect_tuple()
-            .context("malformed")?;
+        let parse = |s: &str| -> anyhow::Result<Vec3> {
+            s.split(',')
+                .map(|n| {
+                    n.trim()
+                        .parse()
+                        .with_context(|| format!("parse failure '{n}'"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("malformed"))
+        };
         Ok(Hailstone {
-            x,
-            y,
-            z,
-            vx,
-            vy,
-            vz,
+            p: parse(position)?,
+            v: parse(velocity)?,
         })
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     const SAMPLE: &'static str = include_str!("../inputs/day24.sample.txt");
-//     const INPUT: &'static str = include_str!("../inputs/day24.input.txt");
-
-//     #[test]
-//     fn test_part_one_sample() {
-//         let ans = part01(SAMPLE).unwrap();
-//         assert_eq!(24000, ans);
-//     }
-
-//     #[test]
-//     fn test_part_one() {
-//         let ans = part01(INPUT).unwrap();
-//         assert_eq!(69501, ans);
-//     }
-
-//     #[test]
-//     fn test_part_two_sample() {
-//         let ans = part02(SAMPLE).unwrap();
-//         assert_eq!(45000, ans);
-//     }
-
-//     #[test]
-//     fn test_part_two() {
-//         let ans = part02(INPUT).unwrap();
-//         assert_eq!(202346, ans);
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_parse() {
+        let h: Hailstone = "19, 13, 30 @ -2,  1, -2".parse().unwrap();
+        assert_eq!([19, 13, 30], h.p);
+        assert_eq!([-2, 1, -2], h.v);
+        assert!("19.5, 13, 30 @ -2, 1, -2".parse::<Hailstone>().is_err());
+        assert!("19, 13 @ -2, 1, -2".parse::<Hailstone>().is_err());
+    }
+
+    #[test]
+    fn test_part_two_sample() {
+        let ans = part02(SAMPLE).unwrap();
+        assert_eq!(47, ans);
+    }
+}
@@ -1,4 +1,8 @@
-use std::collections::{HashSet, VecDeque};
+use anyhow::Context;
+use common::{
+    dot::Dot,
+    graph::{Graph, min_cut},
+};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -8,52 +12,48 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let mut edges = HashSet::new();
-    let mut nodes = HashSet::new();
-    for line in input.lines() {
-        let (lhs, rhs) = line.split_once(": ").unwrap();
-        nodes.insert(lhs.to_string());
-        rhs.split_whitespace().for_each(|s| {
-            nodes.insert(s.to_string());
-            let mut v = [lhs.to_string(), s.to_string()];
-            v.sort();
-            edges.insert((v[0].clone(), v[1].clone()));
-        });
-    }
-
-    // print the dot graph
-    // println!("graph {{");
-    // for (n1, n2) in edges.iter() {
-    //     println!("  {} -- {};", n1, n2);
-    // }
-    // println!("}}");
+    let (graph, edges) = parse_input(input)?;
+    let cut = min_cut(graph.len(), &edges).context("not enough components")?;
+    anyhow::ensure!(
+        cut.size() == 3,
+        "expected to cut 3 wires, found {}",
+        cut.size()
+    );
+    let (a, b) = cut.partition_sizes();
+    Ok(a * b)
+}
 
-    // sample
-    // for e in [("jqt", "nvd"), ("hfx", "pzl"), ("bvb", "cmg")] {
-    for e in [("gsk", "ncg"), ("gmr", "ntx"), ("mrd", "rjs")] {
-        edges.remove(&(e.0.to_string(), e.1.to_string()));
+// Draws the wiring with the wires that have to be cut in red.
+pub fn dot(input: &str) -> anyhow::Result<Dot> {
+    let (graph, edges) = parse_input(input)?;
+    let cut = min_cut(graph.len(), &edges).context("not enough components")?;
+    let mut dot = Dot::graph();
+    for (a, b) in edges {
+        let attrs = if cut.edges.contains(&(a, b)) {
+            vec![("color", "red".to_string()), ("penwidth", "3".to_string())]
+        } else {
+            vec![]
+        };
+        dot.edge(*graph.name(a), *graph.name(b), attrs);
     }
+    Ok(dot)
+}
 
-    let mut queue = VecDeque::new();
-    queue.push_back(nodes.iter().next().unwrap().as_str());
-
-    let mut visited = HashSet::new();
-
-    while let Some(node) = queue.pop_front() {
-        if !visited.insert(node) {
-            continue;
-        }
-
-        for edge in edges.iter() {
-            if edge.0 == node || edge.1 == node {
-                queue.push_back(if edge.0 == node { &edge.1 } else { &edge.0 });
-            }
+// Returns the wiring along with a plain list of its wires, which is what `min_cut` wants.
+fn parse_input(input: &str) -> anyhow::Result<(Graph<&str>, Wires)> {
+    let mut graph = Graph::undirected();
+    for line in input.lines() {
+        let (lhs, rhs) = line.split_once(": ").context("malformed line")?;
+        for other in rhs.split_whitespace() {
+            graph.add_edge(lhs, other);
         }
     }
-
-    Ok(visited.len() * (nodes.len() - visited.len()))
+    let edges = graph.edges().iter().map(|&(a, b, _)| (a, b)).collect();
+    Ok((graph, edges))
 }
 
+type Wires = Vec<(usize, usize)>;
+
 // needs to stay here because of benchmarks
 pub fn part02(_input: &str) -> anyhow::Result<i64> {
     Ok(0)
@@ -65,6 +65,27 @@ mod tests {
 
     const INPUT: &str = include_str!("../inputs/day25.input.txt");
 
+    const SAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_part_one_sample() {
+        let ans = part01(SAMPLE).unwrap();
+        assert_eq!(54, ans);
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT).unwrap();
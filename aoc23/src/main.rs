@@ -2,43 +2,47 @@ use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use clap::Parser;
+use common::dot;
 
 use aoc23::*;
 
 #[derive(Parser)]
 struct Cli {
     day: usize,
+    /// Print the day's input as a graphviz graph instead of solving it
+    #[arg(long)]
+    dot: bool,
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let days = [
-        day01::run,
-        day02::run,
-        day03::run,
-        day04::run,
-        day05::run,
-        day06::run,
-        day07::run,
-        day08::run,
-        day09::run,
-        day10::run,
-        day11::run,
-        day12::run,
-        day13::run,
-        day14::run,
-        day15::run,
-        day16::run,
-        day17::run,
-        day18::run,
-        day19::run,
-        day20::run,
-        day21::run,
-        day22::run,
-        day23::run,
-        day24::run,
-        day25::run,
+    let (days, graphs) = common::days![
+        day01,
+        day02,
+        day03,
+        day04,
+        day05,
+        day06,
+        day07,
+        day08,
+        day09,
+        day10,
+        day11,
+        day12,
+        day13,
+        day14,
+        day15,
+        day16,
+        day17,
+        day18,
+        day19,
+        day20,
+        day21,
+        day22,
+        day23,
+        day24,
+        day25(dot),
     ];
 
     if cli.day > days.len() {
@@ -47,6 +51,10 @@ fn main() -> anyhow::Result<()> {
 
     let input = std::io::read_to_string(std::io::stdin()).context("could not read stdin")?;
 
+    if cli.dot {
+        return dot::print_graph(&graphs, cli.day, &input);
+    }
+
     let start = Instant::now();
     let f = days[cli.day - 1];
     let solve = f(&input)?;
@@ -1,4 +1,5 @@
 use arrayvec::ArrayVec;
+use common::topsort::TopSort;
 use fxhash::FxHashMap;
 use itertools::Itertools;
 
@@ -15,37 +16,40 @@ type VecPages = ArrayVec<usize, MAX_WIDTH>;
 pub fn part01(input: &str) -> anyhow::Result<usize> {
     let (updates, ordering) = parse_input(input);
 
-    Ok(updates
+    updates
         .into_iter()
         .map(|update| {
-            let sorted = topsort(&update, &ordering);
-            if update == sorted {
+            let sorted = topsort(&update, &ordering)?;
+            Ok(if update.as_slice() == sorted {
                 update[update.len() / 2]
             } else {
                 0
-            }
+            })
         })
-        .sum())
+        .sum()
 }
 
 pub fn part02(input: &str) -> anyhow::Result<usize> {
     let (updates, ordering) = parse_input(input);
 
-    Ok(updates
+    updates
         .into_iter()
         .map(|update| {
-            let sorted = topsort(&update, &ordering);
-            if update != sorted {
+            let sorted = topsort(&update, &ordering)?;
+            Ok(if update.as_slice() != sorted {
                 sorted[sorted.len() / 2]
             } else {
                 0
-            }
+            })
         })
-        .sum())
+        .sum()
 }
 
-fn topsort(update: &[usize], ordering: &FxHashMap<usize, VecPages>) -> VecPages {
-    let mut ts = crate::topsort::ArrayTopSort::<usize, MAX_WIDTH>::new();
+fn topsort(update: &[usize], ordering: &FxHashMap<usize, VecPages>) -> anyhow::Result<Vec<usize>> {
+    let mut ts = TopSort::new();
+    for n in update {
+        ts.add_node(*n);
+    }
     for n in update {
         if let Some(depends_on) = ordering.get(n) {
             for v in depends_on {
@@ -55,7 +59,7 @@ fn topsort(update: &[usize], ordering: &FxHashMap<usize, VecPages>) -> VecPages
             }
         }
     }
-    ts.collect()
+    ts.sorted()
 }
 
 fn parse_input(input: &str) -> (Vec<VecPages>, FxHashMap<usize, VecPages>) {
@@ -83,6 +87,43 @@ mod tests {
 
     const INPUT: &str = include_str!("../inputs/day05.input.txt");
 
+    const SAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(143, part01(SAMPLE).unwrap());
+        assert_eq!(123, part02(SAMPLE).unwrap());
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT).unwrap();
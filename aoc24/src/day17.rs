@@ -1,127 +1,222 @@
-use arrayvec::ArrayVec;
+use anyhow::Context;
+use common::asm::{Flow, Instruction, Machine};
 use itertools::{Itertools, iproduct};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
-        part01: part01(input),
+        part01: part01(input)?,
         part02: part02(input)?.to_string(),
     })
 }
 
-pub fn part01(input: &str) -> String {
-    let ((ra, rb, rc), program) = parse_input(input);
-    let mut output = Vec::new();
-    let mut cpu = Cpu {
-        ra,
-        rb,
-        rc,
-        output: |n| {
-            output.push(n);
-            true
-        },
-    };
-    cpu.process(&program);
-    output.into_iter().join(",")
+pub fn part01(input: &str) -> anyhow::Result<String> {
+    let (registers, program) = parse_input(input);
+    let mut cpu = load(registers, &program)?;
+    cpu.run();
+    Ok(cpu.state.output.into_iter().join(","))
 }
 
 pub fn part02(input: &str) -> anyhow::Result<usize> {
-    let (_, program) = parse_input(input);
+    let ((_, rb, rc), program) = parse_input(input);
+
+    // every valid program is a loop that outputs something derived from the low bits of ra, shifts
+    // ra right by 3 and jumps back to the start until ra is 0. so the last output only depends on
+    // the top 3 bits of ra, the one before on the top 6 bits and so on. working backwards from the
+    // end of the program we can find ra 3 bits at a time, keeping every candidate that outputs the
+    // end of the program so far since some of them are dead ends later on.
+    let shifts = program.chunks(2).filter(|instr| instr == &[0, 3]).count();
+    anyhow::ensure!(
+        shifts == 1 && program.ends_with(&[3, 0]),
+        "program doesn't shift ra by 3 once per loop"
+    );
 
-    // got help from reddit, but the general idea is that when looking at the decompilation you can
-    // see that since we always output rb%8 and rb is derived from ra that we only depend on 3 bits
-    // of ra for any given number in the program. so, to find the solution, iterate the program in
-    // reverse and solve for each number.
-    //
-    // manual decompilation:
-    // 2,4 => rb = a % 8
-    // 1,1 => rb = rb ^ 1
-    // 7,5 => rc = ra / 2^rb
-    // 0,3 => ra = ra / 8       // ignore, ra not used after this line
-    // 4,3 => rb = rb ^ rc
-    // 1,6 => rb = rb ^ 6
-    // 5,5 => output(rb % 8)
-    // 3,0 => jump              // 0 so ignore (so loop ends when ra==0)
-
-    let mut candidates = ArrayVec::<usize, 8>::new();
-    candidates.push(0);
-    for n in program.iter().rev() {
-        // there could be multiple numbers that produce the correct result (especially towards the
-        // beginning). we need to try all of them b/c some may not produce a number needed later.
-        let mut next = ArrayVec::<usize, 8>::new();
+    let mut cpu = load((0, rb, rc), &program)?;
+    let mut candidates = vec![0];
+    for len in 1..=program.len() {
+        let expected = &program[program.len() - len..];
+        let mut next = Vec::new();
         for (candidate, i) in iproduct!(candidates, 0..8usize) {
             // << 3 moves the already solved bits left, we then check 0..8 to determine what the
             // next number is
             let ra = (candidate << 3) + i;
-            let mut rb = ra % 8;
-            rb ^= 1;
-            let rc = ra / 2usize.pow(rb as u32);
-            rb ^= rc;
-            rb ^= 6;
-            if rb % 8 == *n {
+            cpu.restart(Registers::new((ra, rb, rc)));
+            if outputs(&mut cpu, expected) {
                 next.push(ra);
             }
         }
         candidates = next;
     }
-    Ok(*candidates.iter().min().unwrap())
+    candidates
+        .into_iter()
+        .min()
+        .context("no value of ra makes the program output itself")
+}
+
+// a machine with the program decoded, two numbers to an instruction
+fn load(registers: (usize, usize, usize), program: &[usize]) -> anyhow::Result<Machine<Instr>> {
+    let program = program
+        .chunks(2)
+        .enumerate()
+        .map(|(i, instr)| match *instr {
+            [opcode, operand] => {
+                Instr::decode(opcode, operand).with_context(|| format!("at {}", i * 2))
+            }
+            _ => anyhow::bail!("opcode {} at {} has no operand", instr[0], i * 2),
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Machine::new(program, Registers::new(registers)))
+}
+
+// whether the program outputs exactly `expected`, stopping as soon as it outputs anything else
+fn outputs(cpu: &mut Machine<Instr>, expected: &[usize]) -> bool {
+    while cpu.step() {
+        if !expected.starts_with(&cpu.state.output) {
+            return false;
+        }
+    }
+    cpu.state.output == expected
+}
+
+/// The program one instruction per line, e.g. `0: bst A    ; B = A % 8`.
+pub fn disassemble(input: &str) -> String {
+    let (_, program) = parse_input(input);
+    let mut out = String::new();
+    for (ip, instr) in program.chunks(2).enumerate() {
+        let ip = ip * 2;
+        match instr {
+            &[opcode, operand] => match Instr::decode(opcode, operand) {
+                Ok(instr) => {
+                    let (mnemonic, arg, comment) = instr.describe();
+                    out.push_str(&format!("{ip:>2}: {mnemonic} {arg:<4} ; {comment}\n"));
+                }
+                Err(_) => out.push_str(&format!("{ip:>2}: data {opcode},{operand}\n")),
+            },
+            _ => out.push_str(&format!("{ip:>2}: data {}\n", instr[0])),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Combo {
+    Literal(usize),
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Instr {
+    Adv(Combo),
+    Bxl(usize),
+    Bst(Combo),
+    Jnz(usize),
+    Bxc,
+    Out(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
 }
 
 #[derive(Debug)]
-struct Cpu<F>
-where
-    F: FnMut(usize) -> bool,
-{
+struct Registers {
     ra: usize,
     rb: usize,
     rc: usize,
-    output: F,
+    output: Vec<usize>,
 }
 
-impl<F> Cpu<F>
-where
-    F: FnMut(usize) -> bool,
-{
-    fn process(&mut self, program: &[usize]) -> bool {
-        let mut ip = 0;
-        while ip < program.len() {
-            let opcode = program[ip];
-            let operand = program[ip + 1];
-            let mut ip_next = ip + 2;
-            match opcode {
-                0 => self.ra /= 2usize.pow(self.decode_combo(operand) as u32),
-                1 => self.rb ^= operand,
-                2 => self.rb = self.decode_combo(operand) % 8,
-                3 => {
-                    if self.ra != 0 {
-                        ip_next = operand;
-                    }
-                }
-                4 => self.rb ^= self.rc, // ignores operand
-                5 => {
-                    let v = self.decode_combo(operand) % 8;
-                    if !(self.output)(v) {
-                        return false;
-                    }
-                }
-                6 => self.rb = self.ra / 2usize.pow(self.decode_combo(operand) as u32),
-                7 => self.rc = self.ra / 2usize.pow(self.decode_combo(operand) as u32),
-                _ => unreachable!("unknown opcode {opcode}"),
-            }
-            ip = ip_next;
+impl Registers {
+    fn new((ra, rb, rc): (usize, usize, usize)) -> Self {
+        Self {
+            ra,
+            rb,
+            rc,
+            output: Vec::new(),
         }
-        true
     }
 
-    fn decode_combo(&self, operand: usize) -> usize {
+    fn combo(&self, operand: Combo) -> usize {
         match operand {
-            0..=3 => operand,
-            4 => self.ra,
-            5 => self.rb,
-            6 => self.rc,
-            7 => unreachable!("combo(7): invalid instruction"),
-            _ => unreachable!(),
+            Combo::Literal(n) => n,
+            Combo::A => self.ra,
+            Combo::B => self.rb,
+            Combo::C => self.rc,
         }
     }
+
+    // ra / 2^combo, which is 0 once the shift is wider than ra
+    fn shift(&self, operand: Combo) -> usize {
+        let bits = u32::try_from(self.combo(operand)).unwrap_or(u32::MAX);
+        self.ra.checked_shr(bits).unwrap_or(0)
+    }
+}
+
+impl Instr {
+    fn decode(opcode: usize, operand: usize) -> anyhow::Result<Self> {
+        let combo = || match operand {
+            0..=3 => Ok(Combo::Literal(operand)),
+            4 => Ok(Combo::A),
+            5 => Ok(Combo::B),
+            6 => Ok(Combo::C),
+            _ => anyhow::bail!("invalid combo operand {operand}"),
+        };
+        Ok(match opcode {
+            0 => Instr::Adv(combo()?),
+            1 => Instr::Bxl(operand),
+            2 => Instr::Bst(combo()?),
+            // instructions are two numbers long
+            3 if operand.is_multiple_of(2) => Instr::Jnz(operand / 2),
+            3 => anyhow::bail!("jump into the middle of an instruction"),
+            4 => Instr::Bxc, // ignores operand
+            5 => Instr::Out(combo()?),
+            6 => Instr::Bdv(combo()?),
+            7 => Instr::Cdv(combo()?),
+            _ => anyhow::bail!("unknown opcode {opcode}"),
+        })
+    }
+
+    // the mnemonic, its argument and what it does
+    fn describe(&self) -> (&'static str, String, String) {
+        let combo = |c: &Combo| match c {
+            Combo::Literal(n) => n.to_string(),
+            Combo::A => "A".to_string(),
+            Combo::B => "B".to_string(),
+            Combo::C => "C".to_string(),
+        };
+        match self {
+            Instr::Adv(c) => ("adv", combo(c), format!("A = A >> {}", combo(c))),
+            Instr::Bxl(n) => ("bxl", n.to_string(), format!("B = B ^ {n}")),
+            Instr::Bst(c) => ("bst", combo(c), format!("B = {} % 8", combo(c))),
+            Instr::Jnz(ip) => (
+                "jnz",
+                (ip * 2).to_string(),
+                format!("if A != 0 goto {}", ip * 2),
+            ),
+            Instr::Bxc => ("bxc", String::new(), "B = B ^ C".to_string()),
+            Instr::Out(c) => ("out", combo(c), format!("output {} % 8", combo(c))),
+            Instr::Bdv(c) => ("bdv", combo(c), format!("B = A >> {}", combo(c))),
+            Instr::Cdv(c) => ("cdv", combo(c), format!("C = A >> {}", combo(c))),
+        }
+    }
+}
+
+impl Instruction for Instr {
+    type State = Registers;
+
+    fn execute(&self, r: &mut Registers) -> Flow {
+        match *self {
+            Instr::Adv(c) => r.ra = r.shift(c),
+            Instr::Bxl(n) => r.rb ^= n,
+            Instr::Bst(c) => r.rb = r.combo(c) % 8,
+            Instr::Jnz(ip) if r.ra != 0 => return Flow::Goto(ip),
+            Instr::Jnz(_) => (),
+            Instr::Bxc => r.rb ^= r.rc,
+            Instr::Out(c) => r.output.push(r.combo(c) % 8),
+            Instr::Bdv(c) => r.rb = r.shift(c),
+            Instr::Cdv(c) => r.rc = r.shift(c),
+        }
+        Flow::Next
+    }
 }
 
 fn parse_input(input: &str) -> ((usize, usize, usize), Vec<usize>) {
@@ -145,9 +240,41 @@ mod tests {
 
     const INPUT: &str = include_str!("../inputs/day17.input.txt");
 
+    #[test]
+    fn test_samples() {
+        let sample = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", part01(sample).unwrap());
+        let quine = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0";
+        assert_eq!(117440, part02(quine).unwrap());
+        assert!(part02(sample).is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        let err = load((0, 0, 0), &[0, 3, 5, 4, 3]).unwrap_err();
+        assert_eq!("opcode 3 at 4 has no operand", format!("{err:#}"));
+        let err = load((0, 0, 0), &[0, 3, 5, 7]).unwrap_err();
+        assert_eq!("at 2: invalid combo operand 7", format!("{err:#}"));
+        let err = load((0, 0, 0), &[3, 1]).unwrap_err();
+        assert_eq!(
+            "at 0: jump into the middle of an instruction",
+            format!("{err:#}")
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let quine = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0";
+        let expected = " 0: adv 3    ; A = A >> 3
+ 2: out A    ; output A % 8
+ 4: jnz 0    ; if A != 0 goto 0
+";
+        assert_eq!(expected, disassemble(quine));
+    }
+
     #[test]
     fn test_part_one() {
-        let ans = part01(INPUT);
+        let ans = part01(INPUT).unwrap();
         assert_eq!("2,0,7,3,0,3,1,3,7", ans);
     }
 
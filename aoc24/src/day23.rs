@@ -1,6 +1,6 @@
-use fxhash::{FxHashMap, FxHashSet};
+use common::graph::Graph;
+use fxhash::FxHashSet;
 use itertools::Itertools;
-use string_interner::{StringInterner, symbol::SymbolU32};
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -10,85 +10,33 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let mut graph = FxHashMap::<&str, Vec<&str>>::default();
-    for line in input.lines() {
-        let (l, r) = line.split_once('-').unwrap();
-        graph.entry(l).or_default().push(r);
-        graph.entry(r).or_default().push(l);
-    }
-
-    let mut cliques = FxHashSet::default();
-    for node in graph.keys().filter(|node| node.starts_with('t')) {
-        for v in &graph[node] {
-            for u in &graph[v] {
-                if graph[node].contains(u) {
-                    let mut clique = [node, v, u];
-                    clique.sort();
-                    cliques.insert(clique);
-                }
-            }
-        }
+    let graph = parse_input(input)?;
+    let mut triangles = FxHashSet::default();
+    for node in (0..graph.len()).filter(|&n| graph.name(n).starts_with('t')) {
+        triangles.extend(graph.cliques_containing(node, 3));
     }
-
-    Ok(cliques.len())
+    Ok(triangles.len())
 }
 
 pub fn part02(input: &str) -> anyhow::Result<String> {
-    // performance optimization: intern the strings
-    let mut graph = FxHashMap::<SymbolU32, FxHashSet<SymbolU32>>::default();
-    let mut interner = StringInterner::default();
-    for line in input.lines() {
-        let (l, r) = line.split_once('-').unwrap();
-        let l = interner.get_or_intern(l);
-        let r = interner.get_or_intern(r);
-        graph.entry(l).or_default().insert(r);
-        graph.entry(r).or_default().insert(l);
-    }
-
-    let mut vertices = graph.keys().cloned().collect();
-    let largest_network = maximal_clique_pivot(
-        &graph,
-        &mut FxHashSet::default(),
-        &mut vertices,
-        &mut FxHashSet::default(),
-    );
-    Ok(largest_network
+    let graph = parse_input(input)?;
+    Ok(graph
+        .max_clique()
         .into_iter()
-        .map(|sym| interner.resolve(sym).unwrap())
+        .map(|id| *graph.name(id))
         .sorted()
         .join(","))
 }
 
-// bron kerbasch
-fn maximal_clique_pivot(
-    graph: &FxHashMap<SymbolU32, FxHashSet<SymbolU32>>,
-    r: &mut FxHashSet<SymbolU32>,
-    p: &mut FxHashSet<SymbolU32>,
-    x: &mut FxHashSet<SymbolU32>,
-) -> FxHashSet<SymbolU32> {
-    if p.is_empty() && x.is_empty() {
-        return r.clone();
-    }
-
-    let pivot = p.union(x).max_by_key(|v| graph[v].len()).unwrap();
-
-    let mut max = FxHashSet::default();
-    for v in p.clone().difference(&graph[pivot]) {
-        r.insert(*v);
-
-        let mut pv = p.intersection(&graph[v]).cloned().collect();
-        let mut xv = x.intersection(&graph[v]).cloned().collect();
-
-        let clique = maximal_clique_pivot(graph, r, &mut pv, &mut xv);
-        if clique.len() > max.len() {
-            max = clique;
-        }
-
-        r.remove(v);
-        p.remove(v);
-        x.insert(*v);
+fn parse_input(input: &str) -> anyhow::Result<Graph<&str>> {
+    let mut graph = Graph::undirected();
+    for line in input.lines() {
+        let (l, r) = line
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("malformed line {line}"))?;
+        graph.add_edge(l, r);
     }
-    max
+    Ok(graph)
 }
 
 #[cfg(test)]
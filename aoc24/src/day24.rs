@@ -1,9 +1,11 @@
-use bittle::Bits;
-use fxhash::FxHashMap;
+use anyhow::Context;
+use common::{
+    dot::{Dot, ToDot},
+    netlist::{Logic, Netlist, Operand},
+};
+use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
 
-use crate::topsort::TopSort;
-
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
         part01: part01(input)?.to_string(),
@@ -12,191 +14,253 @@ pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
 }
 
 pub fn part01(input: &str) -> anyhow::Result<usize> {
-    let (wires, gates) = input.split_once("\n\n").unwrap();
-    let mut wires: FxHashMap<&str, bool> = wires
-        .lines()
-        .map(|s| {
-            let (wire, value) = s.split_once(": ").unwrap();
-            let v = value.parse::<u8>().unwrap();
-            (wire, v == 1)
-        })
-        .collect();
-    let gates: Vec<(&str, &str, &str, &str)> = gates
-        .lines()
-        .map(|s| {
-            let mut it = s.split_whitespace();
-            let lhs = it.next().unwrap();
-            let op = it.next().unwrap();
-            let rhs = it.next().unwrap();
-            it.next(); // skip ->
-            let out = it.next().unwrap();
-            (lhs, op, rhs, out)
-        })
-        .collect();
-
-    let mut topsort = TopSort::default();
-    let mut ops: FxHashMap<&str, (&str, &str, &str)> = FxHashMap::default();
-    for gate in &gates {
-        topsort.add_dependency(gate.0, gate.3);
-        topsort.add_dependency(gate.2, gate.3);
-
-        assert!(!ops.contains_key(gate.3));
-        ops.insert(gate.3, (gate.0, gate.1, gate.2));
-    }
-
-    for wire in topsort {
-        if !ops.contains_key(wire) {
-            continue;
-        }
-        let (lhs, op, rhs) = ops[wire];
-        let res = match op {
-            "AND" => wires[lhs] && wires[rhs],
-            "OR" => wires[lhs] || wires[rhs],
-            "XOR" => wires[lhs] != wires[rhs],
-            _ => unreachable!("invalid op {op}"),
-        };
-        wires.insert(wire, res);
-    }
+    let (wires, gates) = parse_input(input);
+    let mut circuit = circuit(&wires, &gates, &FxHashMap::default());
+    output(&mut circuit, &gates).context("circuit does not drive every output")
+}
 
-    Ok(decode(&wires, 'z'))
+// Draws the circuit, with every gate coloured by what it does.
+pub fn dot(input: &str) -> anyhow::Result<Dot> {
+    let (wires, gates) = parse_input(input);
+    Ok(circuit(&wires, &gates, &FxHashMap::default()).to_dot())
 }
 
-// This doesn't actually solve the input, it's just code that I used to manually solve the problem.
-// Conceptually, this is a binary adder problem. I noticed that many of the z outputs (which are
-// the bits of the final sum) were not XORs as they should be:
+// The circuit is supposed to be a ripple-carry adder, z = x + y, but four pairs of gates have
+// their output wires swapped. Every bit of a correct adder is built from the same gates:
 //
-// ❯ cat aoc24/inputs/day24.input.txt | grep -v XOR | grep z
-// cdc OR stq -> z21
-// x12 AND y12 -> z12
-// jbr AND wcs -> z33
-// mgw OR bds -> z45 (OK - final carry)
-//
-// So, I created a graphviz and manually debugged what was wrong with those binary adders. Turns
-// out that the last bit (z45) was correct since it was the carry-out of the 44th bit.
-//
-// After fixing 3/4 of those, I set all of the bits in the input numbers (x and y) to 1 to force
-// carry through the chain. I then checked the result against the expected result and found the
-// last incorrect adder in the chain.
-//
-// Helpful Details:
-//   https://www.electronics-tutorials.ws/combination/comb_7.html
-//
-//   Half adder:
+//   Half adder (bit 0):
 //   SUM = A XOR B
 //   CARRY = A AND B
 //
 //   Full adder:
 //   SUM = (A XOR B) XOR Cin
 //   CARRY-OUT = A AND B OR Cin(A XOR B)
+//
+//   https://www.electronics-tutorials.ws/combination/comb_7.html
+//
+// So we can find the miswired outputs by checking that every gate plays one of those roles, and
+// then work out which of them pair up by simulating the repaired circuit.
 pub fn part02(input: &str) -> anyhow::Result<String> {
-    let swaps = FxHashMap::from_iter([
-        ("nhn", "z21"),
-        ("z21", "nhn"),
-        ("vdc", "z12"),
-        ("z12", "vdc"),
-        ("gst", "z33"),
-        ("z33", "gst"),
-        ("khg", "tvb"),
-        ("tvb", "khg"),
-    ]);
-
-    let (wires, gates) = input.split_once("\n\n").unwrap();
-    // for the purposes of verifying the binary adder, we set all the input wires to 1 so we can
-    // determine where the binary adder is broken
-    let mut wires: FxHashMap<&str, bool> = wires
-        .lines()
-        .map(|s| {
-            let (wire, _value) = s.split_once(": ").unwrap();
-            // let v = value.parse::<u8>().unwrap();
-            (wire, true)
-        })
-        .collect();
-    let gates: Vec<(&str, &str, &str, &str)> = gates
-        .lines()
-        .map(|s| {
-            let mut it = s.split_whitespace();
-            let lhs = it.next().unwrap();
-            let op = it.next().unwrap();
-            let rhs = it.next().unwrap();
-            it.next(); // skip ->
-            let out = it.next().unwrap();
-            (lhs, op, rhs, out)
-        })
-        .collect();
+    let (wires, gates) = parse_input(input);
+    let suspects = miswired_outputs(&wires, &gates);
+    let swaps = find_swaps(&wires, &gates, &suspects)
+        .context("no way to swap the miswired outputs that repairs the adder")?;
+    Ok(swaps
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .sorted()
+        .join(","))
+}
 
-    // let mut file = File::create("./aoc24/src/day24.dot")?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
 
-    let mut topsort = TopSort::default();
-    let mut ops: FxHashMap<&str, (&str, &str, &str)> = FxHashMap::default();
-    // writeln!(&mut file, "digraph G {{")?;
-    for gate in &gates {
-        let out = swaps.get(gate.3).cloned().unwrap_or(gate.3);
+#[derive(Debug, Clone, Copy)]
+struct Gate<'a> {
+    lhs: &'a str,
+    op: Op,
+    rhs: &'a str,
+    out: &'a str,
+}
 
-        topsort.add_dependency(gate.0, out);
-        topsort.add_dependency(gate.2, out);
-        assert!(
-            !ops.contains_key(out),
-            "duplicate output wire :: out = {out}"
+impl Gate<'_> {
+    fn logic(&self) -> Logic<1> {
+        let (lhs, rhs) = (
+            Operand::Wire(self.lhs.to_string()),
+            Operand::Wire(self.rhs.to_string()),
         );
-        ops.insert(out, (gate.0, gate.1, gate.2));
-        // let color = match gate.1 {
-        //     "AND" => "green",
-        //     "OR" => "blue",
-        //     "XOR" => "red",
-        //     _ => unreachable!(),
-        // };
-        // writeln!(&mut file, "{} [color = {}]", out, color)?;
-        // writeln!(&mut file, "{} -> {};", gate.0, out)?;
-        // writeln!(&mut file, "{} -> {};", gate.2, out)?;
+        match self.op {
+            Op::And => Logic::And(lhs, rhs),
+            Op::Or => Logic::Or(lhs, rhs),
+            Op::Xor => Logic::Xor(lhs, rhs),
+        }
     }
-    // writeln!(&mut file, "}}")?;
 
-    let x = decode(&wires, 'x');
-    let y = decode(&wires, 'y');
+    fn reads_inputs(&self) -> bool {
+        [self.lhs, self.rhs]
+            .iter()
+            .all(|w| w.starts_with('x') || w.starts_with('y'))
+    }
 
-    for wire in topsort {
-        if !ops.contains_key(wire) {
-            continue;
-        }
-        let (lhs, op, rhs) = ops[wire];
-        let res = match op {
-            "AND" => wires[lhs] && wires[rhs],
-            "OR" => wires[lhs] || wires[rhs],
-            "XOR" => wires[lhs] != wires[rhs],
-            _ => unreachable!("invalid op {op}"),
+    fn reads_first_bit(&self) -> bool {
+        [self.lhs, self.rhs].iter().all(|w| &w[1..] == "00")
+    }
+}
+
+// Finds the output wires of every gate that doesn't fit in a ripple-carry adder.
+fn miswired_outputs<'a>(wires: &FxHashMap<&str, bool>, gates: &[Gate<'a>]) -> Vec<&'a str> {
+    let bits = wires.keys().filter(|w| w.starts_with('x')).count();
+    let last_z = format!("z{bits:02}");
+
+    let mut consumers: FxHashMap<&str, Vec<Op>> = FxHashMap::default();
+    for gate in gates {
+        consumers.entry(gate.lhs).or_default().push(gate.op);
+        consumers.entry(gate.rhs).or_default().push(gate.op);
+    }
+    let feeds = |wire: &str, op: Op| consumers.get(wire).is_some_and(|ops| ops.contains(&op));
+
+    let mut suspects = FxHashSet::default();
+    for gate in gates {
+        let to_z = gate.out.starts_with('z');
+        let wrong = match gate.op {
+            // the final carry out is the only output that isn't a sum
+            _ if gate.out == last_z => gate.op != Op::Or,
+            Op::And | Op::Or if to_z => true,
+            // a sum is either A XOR B, which then goes through another XOR with the carry in, or
+            // that second XOR which is an output bit
+            Op::Xor if gate.reads_inputs() => {
+                !gate.reads_first_bit() && (to_z || !feeds(gate.out, Op::Xor))
+            }
+            Op::Xor => !to_z,
+            // both halves of the carry are combined with an OR (except for the half adder which
+            // has no carry in)
+            Op::And => !gate.reads_first_bit() && !feeds(gate.out, Op::Or),
+            Op::Or => false,
         };
-        wires.insert(wire, res);
+        if wrong {
+            suspects.insert(gate.out);
+        }
     }
+    suspects.into_iter().sorted().collect()
+}
 
-    let z = decode(&wires, 'z');
+// Pairs up the suspect wires, returning the swaps that make the circuit add correctly.
+fn find_swaps<'a>(
+    wires: &FxHashMap<&'a str, bool>,
+    gates: &[Gate<'a>],
+    suspects: &[&'a str],
+) -> Option<Vec<(&'a str, &'a str)>> {
+    if suspects.is_empty() {
+        return is_adder(wires, gates, &[]).then(Vec::new);
+    }
+    if suspects.len() % 2 == 1 {
+        return None;
+    }
 
-    let xz = x + y;
-    for i in 0..64u32 {
-        if xz.test_bit(i) == z.test_bit(i) {
+    // try every way of splitting the suspects into pairs
+    let mut pairs = Vec::new();
+    let mut used = vec![false; suspects.len()];
+    try_pairings(wires, gates, suspects, &mut used, &mut pairs).then_some(pairs)
+}
+
+fn try_pairings<'a>(
+    wires: &FxHashMap<&'a str, bool>,
+    gates: &[Gate<'a>],
+    suspects: &[&'a str],
+    used: &mut [bool],
+    pairs: &mut Vec<(&'a str, &'a str)>,
+) -> bool {
+    let Some(first) = used.iter().position(|u| !u) else {
+        return is_adder(wires, gates, pairs);
+    };
+    used[first] = true;
+    for second in first + 1..suspects.len() {
+        if used[second] {
             continue;
         }
-        println!("bit #{i} incorrect");
+        used[second] = true;
+        pairs.push((suspects[first], suspects[second]));
+        if try_pairings(wires, gates, suspects, used, pairs) {
+            return true;
+        }
+        pairs.pop();
+        used[second] = false;
     }
+    used[first] = false;
+    false
+}
 
-    println!("x = {x}, y = {y}");
-    println!("ez = {:b}", x + y);
-    println!(" z = {z:b}");
+// Simulates the circuit with the swaps applied on inputs that exercise every bit and the carry
+// chain to check that it really is an adder.
+fn is_adder<'a>(
+    wires: &FxHashMap<&'a str, bool>,
+    gates: &[Gate<'a>],
+    swaps: &[(&'a str, &'a str)],
+) -> bool {
+    let swaps: FxHashMap<&str, &str> = swaps.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
 
-    Ok(swaps.keys().sorted().join(","))
+    let bits = wires.keys().filter(|w| w.starts_with('x')).count();
+    let all = (1usize << bits) - 1;
+    let mut cases = vec![(0, 0), (all, 1), (all, all)];
+    for bit in 0..bits {
+        cases.extend([(1 << bit, 0), (0, 1 << bit), (1 << bit, 1 << bit)]);
+    }
+
+    let mut circuit = circuit(wires, gates, &swaps);
+    cases.into_iter().all(|(x, y)| {
+        for &w in wires.keys() {
+            let bit = w[1..].parse::<usize>().unwrap();
+            let n = if w.starts_with('x') { x } else { y };
+            circuit.set(w, ((n >> bit) & 1) as u64);
+        }
+        output(&mut circuit, gates) == Some(x + y)
+    })
+}
+
+// Builds the circuit with the outputs of the gates in `swaps` exchanged.
+fn circuit(
+    wires: &FxHashMap<&str, bool>,
+    gates: &[Gate],
+    swaps: &FxHashMap<&str, &str>,
+) -> Netlist<Logic<1>> {
+    let inputs = wires
+        .iter()
+        .map(|(w, v)| (w.to_string(), Logic::Buf(Operand::Value(*v as u64))));
+    let gates = gates.iter().map(|gate| {
+        let out = swaps.get(gate.out).copied().unwrap_or(gate.out);
+        (out.to_string(), gate.logic())
+    });
+    inputs.chain(gates).collect()
 }
 
-fn decode(wires: &FxHashMap<&str, bool>, starting_with: char) -> usize {
+// Reads the number on the z wires, or `None` if some of them are never driven (e.g. the swaps
+// made a loop).
+fn output(circuit: &mut Netlist<Logic<1>>, gates: &[Gate]) -> Option<usize> {
     let mut n = 0;
-    for (_, value) in wires
+    for z in gates
         .iter()
-        .filter(|(wire, _)| wire.starts_with(starting_with))
+        .map(|g| g.out)
+        .filter(|w| w.starts_with('z'))
         .sorted()
         .rev()
     {
-        n <<= 1;
-        n |= *value as usize;
+        n = n << 1 | circuit.get(z).ok()? as usize;
     }
-    n
+    Some(n)
+}
+
+fn parse_input(input: &str) -> (FxHashMap<&str, bool>, Vec<Gate<'_>>) {
+    let (wires, gates) = input.split_once("\n\n").unwrap();
+    let wires = wires
+        .lines()
+        .map(|s| {
+            let (wire, value) = s.split_once(": ").unwrap();
+            let v = value.parse::<u8>().unwrap();
+            (wire, v == 1)
+        })
+        .collect();
+    let gates = gates
+        .lines()
+        .map(|s| {
+            let mut it = s.split_whitespace();
+            let lhs = it.next().unwrap();
+            let op = match it.next().unwrap() {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "XOR" => Op::Xor,
+                op => unreachable!("invalid op {op}"),
+            };
+            let rhs = it.next().unwrap();
+            it.next(); // skip ->
+            let out = it.next().unwrap();
+            Gate { lhs, op, rhs, out }
+        })
+        .collect();
+    (wires, gates)
 }
 
 #[cfg(test)]
@@ -211,6 +275,53 @@ mod tests {
         assert_eq!(51410244478064, ans);
     }
 
+    // builds a ripple-carry adder for `bits` bit numbers with the given outputs swapped
+    fn adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut lines = vec![];
+        for bit in 0..bits {
+            lines.push(format!("x{bit:02}: 1"));
+            lines.push(format!("y{bit:02}: 0"));
+        }
+        lines.push(String::new());
+
+        lines.push("x00 XOR y00 -> z00".to_string());
+        lines.push("x00 AND y00 -> c00".to_string());
+        for bit in 1..bits {
+            let (x, y) = (format!("x{bit:02}"), format!("y{bit:02}"));
+            let carry_in = format!("c{:02}", bit - 1);
+            let carry_out = if bit == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                format!("c{bit:02}")
+            };
+            lines.push(format!("{x} XOR {y} -> s{bit:02}"));
+            lines.push(format!("{x} AND {y} -> a{bit:02}"));
+            lines.push(format!("s{bit:02} XOR {carry_in} -> z{bit:02}"));
+            lines.push(format!("s{bit:02} AND {carry_in} -> b{bit:02}"));
+            lines.push(format!("a{bit:02} OR b{bit:02} -> {carry_out}"));
+        }
+
+        let swaps: FxHashMap<&str, &str> =
+            swaps.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+        lines
+            .into_iter()
+            .map(|line| match line.split_once(" -> ") {
+                Some((gate, out)) => format!("{gate} -> {}", swaps.get(out).unwrap_or(&out)),
+                None => line,
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn test_part_two_generated() {
+        let circuit = adder(8, &[]);
+        assert_eq!(255, part01(&circuit).unwrap());
+        assert_eq!("", part02(&circuit).unwrap());
+
+        let circuit = adder(8, &[("z03", "b03"), ("s05", "a05"), ("c06", "z06")]);
+        assert_eq!("a05,b03,c06,s05,z03,z06", part02(&circuit).unwrap());
+    }
+
     #[test]
     fn test_part_two() {
         let ans = part02(INPUT).unwrap();
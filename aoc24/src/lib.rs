@@ -24,8 +24,6 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
-pub mod topsort;
-
 pub struct SolveInfo {
     pub part01: String,
     pub part02: String,
@@ -1,7 +1,7 @@
+use common::unionfind::{UnionFind, kruskal};
 use itertools::Itertools;
-use ordered_float::NotNan;
-use std::{cmp::Reverse, collections::HashMap};
-use union_find::{QuickFindUf, QuickUnionUf, UnionBySize, UnionFind};
+
+type Point = (usize, usize, usize);
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -20,80 +20,40 @@ pub fn part01(input: &str) -> anyhow::Result<usize> {
 
     let boxes = parse_input(input);
 
-    // sort all pairs by distance
-    let mut pairs = Vec::with_capacity(499500); // 1000 choose 2
-    for combo in boxes.clone().into_iter().combinations(2) {
-        let (a, b) = (combo[0], combo[1]);
-        let dx = a.0 as f64 - b.0 as f64;
-        let dy = a.1 as f64 - b.1 as f64;
-        let dz = a.2 as f64 - b.2 as f64;
-
-        let d = (dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt();
-        let d = NotNan::new(d).unwrap();
-        pairs.push((a, b, d));
-    }
-    pairs.sort_by_key(|(_, _, d)| *d);
-
     // use union-find to connect the shortest pairs
-    let mut uf = QuickUnionUf::<UnionBySize>::new(boxes.len());
-    for (a, b, _) in &pairs[0..n_shortest_connections] {
-        let ai = boxes.iter().position(|x| x == a).unwrap();
-        let bi = boxes.iter().position(|x| x == b).unwrap();
-        uf.union(ai, bi);
+    let mut uf: UnionFind<Point> = boxes.iter().copied().collect();
+    for (a, b, _) in pairs(&boxes).into_iter().take(n_shortest_connections) {
+        uf.union(a, b);
     }
 
-    // build circuits based on where union-find algo put them
-    let mut circuits = HashMap::new();
-    (0..boxes.len()).for_each(|i| {
-        _ = circuits
-            .entry(uf.find(i))
-            .and_modify(|len| *len += 1)
-            .or_insert(1)
-    });
-
     // product of top3 circuit sizes
-    Ok(circuits
-        .values()
-        .sorted_by_key(|l| Reverse(*l))
-        .take(3)
-        .product::<usize>())
+    Ok(uf.set_sizes().into_iter().take(3).product())
 }
 
+// connecting the closest pairs until everything is one circuit is Kruskal's algorithm, so the
+// answer comes from the last edge of the minimum spanning tree
 pub fn part02(input: &str) -> anyhow::Result<usize> {
     let boxes = parse_input(input);
+    let tree = kruskal(boxes.iter().copied(), pairs(&boxes));
+    anyhow::ensure!(tree.len() + 1 == boxes.len(), "boxes never all connect");
+    let (a, b, _) = tree.last().ok_or_else(|| anyhow::anyhow!("no boxes"))?;
+    Ok(a.0 * b.0)
+}
 
-    // sort all pairs by distance
-    let mut pairs = Vec::with_capacity(499500); // 1000 choose 2
-    for combo in boxes.clone().into_iter().combinations(2) {
-        let (a, b) = (combo[0], combo[1]);
-        let dx = a.0 as f64 - b.0 as f64;
-        let dy = a.1 as f64 - b.1 as f64;
-        let dz = a.2 as f64 - b.2 as f64;
-
-        let d = (dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt();
-        let d = NotNan::new(d).unwrap();
-        pairs.push((a, b, d));
-    }
-    pairs.sort_by_key(|(_, _, d)| *d);
-
-    // union pairs until all boxes connected
-    let mut uf = QuickFindUf::<UnionBySize>::new(boxes.len());
-    for (a, b, _) in pairs {
-        let ai = boxes.iter().position(|x| *x == a).unwrap();
-        let bi = boxes.iter().position(|x| *x == b).unwrap();
-        if !uf.union(ai, bi) {
-            // boxes were in same set, no need to check for change
-            continue;
-        }
-
-        if (0..boxes.len()).map(|i| uf.find(i)).all_equal() {
-            return Ok(a.0 * b.0);
-        }
-    }
-    unreachable!()
+// every pair of boxes with their squared distance, closest first
+fn pairs(boxes: &[Point]) -> Vec<(Point, Point, usize)> {
+    boxes
+        .iter()
+        .tuple_combinations()
+        .map(|(&a, &b)| {
+            let d = a.0.abs_diff(b.0).pow(2) + a.1.abs_diff(b.1).pow(2) + a.2.abs_diff(b.2).pow(2);
+            (a, b, d)
+        })
+        .sorted_by_key(|(_, _, d)| *d)
+        .collect()
 }
 
-fn parse_input(input: &str) -> Vec<(usize, usize, usize)> {
+fn parse_input(input: &str) -> Vec<Point> {
     let mut boxes = Vec::new();
     for line in input.lines() {
         let nums: Vec<usize> = line.splitn(3, ',').map(|s| s.parse().unwrap()).collect();
@@ -108,6 +68,40 @@ mod tests {
 
     const INPUT: &str = include_str!("../inputs/day08.input.txt");
 
+    const SAMPLE: &str = "\
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689";
+
+    #[test]
+    fn test_part_one_sample() {
+        let ans = part01(SAMPLE).unwrap();
+        assert_eq!(40, ans);
+    }
+
+    #[test]
+    fn test_part_two_sample() {
+        let ans = part02(SAMPLE).unwrap();
+        assert_eq!(25272, ans);
+    }
+
     #[test]
     fn test_part_one() {
         let ans = part01(INPUT).unwrap();
@@ -1,9 +1,10 @@
-use std::{
-    collections::VecDeque,
-    ops::{Add, Div, Mul, Sub},
-};
+use std::collections::VecDeque;
 
-use num::integer::gcd;
+use common::{
+    fraction::Fraction,
+    linalg::{LinearSystem, Matrix},
+};
+use num::One;
 
 pub fn run(input: &str) -> anyhow::Result<crate::SolveInfo> {
     Ok(crate::SolveInfo {
@@ -26,222 +27,43 @@ pub fn part02(input: &str) -> anyhow::Result<usize> {
 }
 
 fn fewest_button_presses_joltage(machine: Machine) -> usize {
-    // convert to equations
-    let mut equations = Vec::new();
-    for (slot_index, j) in machine.joltage.iter().enumerate() {
-        let mut eq = vec![Fraction::zero(); machine.buttons.len() + 1];
-        for (btn_idx, btn) in machine.buttons.iter().enumerate() {
-            if btn.contains(&slot_index) {
-                eq[btn_idx] = Fraction::one();
-            }
-        }
-        eq[machine.buttons.len()] = Fraction::new(*j as i128, 1);
-        equations.push(eq);
-    }
-
-    // rref
-    let mut pivot_row = 0;
-    let mut pivots = Vec::new();
-    // exclude rhs from loop
-    for col in 0..machine.buttons.len() {
-        let found = (pivot_row..equations.len()).find(|row| !equations[*row][col].is_zero());
-        if found.is_none() {
-            continue;
-        }
-        let found = found.unwrap();
-
-        equations.swap(pivot_row, found);
-
-        let pivot_value = equations[pivot_row][col];
-        for c in 0..equations[pivot_row].len() {
-            equations[pivot_row][c] = equations[pivot_row][c] / pivot_value;
-        }
-
-        for row in 0..equations.len() {
-            if row == pivot_row {
-                continue;
-            }
-
-            let factor = equations[row][col];
-            if factor.is_zero() {
-                continue;
-            }
-
-            for c in 0..equations[0].len() {
-                equations[row][c] = equations[row][c] - factor * equations[pivot_row][c];
-            }
-        }
-
-        pivots.push((pivot_row, col));
-        pivot_row += 1;
-
-        if pivot_row == equations.len() {
-            break;
-        }
-    }
-
-    // check for inconsistency
-    for eq in &equations {
-        let all_zero = eq[..machine.buttons.len()].iter().all(Fraction::is_zero);
-        let rhs_nonzero = !eq[machine.buttons.len()].is_zero();
-        if all_zero && rhs_nonzero {
-            unreachable!("no solution");
+    // convert to equations, one per joltage slot, where each variable is the number of times a
+    // button is pressed
+    let mut a = Matrix::zeros(machine.joltage.len(), machine.buttons.len());
+    for (btn_idx, btn) in machine.buttons.iter().enumerate() {
+        for &slot_index in btn {
+            a[(slot_index, btn_idx)] = Fraction::one();
         }
     }
+    let b = machine
+        .joltage
+        .iter()
+        .map(|&j| Fraction::from_integer(j as i128))
+        .collect();
+    let system = LinearSystem::new(a, b);
+    assert!(system.is_consistent(), "no solution");
 
-    let pivot_cols: Vec<usize> = pivots.iter().map(|&(_, col)| col).collect();
-    let free_cols: Vec<usize> = (0..machine.buttons.len())
-        .filter(|col| !pivot_cols.contains(col))
+    // a button can't be pressed more times than the smallest joltage it increases
+    let upper_bounds: Vec<u64> = machine
+        .buttons
+        .iter()
+        .map(|btn| {
+            btn.iter()
+                .map(|&slot| machine.joltage[slot] as u64)
+                .min()
+                .unwrap_or(0)
+        })
         .collect();
 
-    let mut solution = vec![Fraction::zero(); machine.buttons.len()];
     let mut best = None;
-    enumerate_free_variables(
-        0,
-        &free_cols,
-        &machine,
-        &equations,
-        &pivots,
-        &mut solution,
-        &mut best,
-    );
-
-    best.expect("no solution found")
-}
-
-fn enumerate_free_variables(
-    free_index: usize,
-    free_cols: &[usize],
-    machine: &Machine,
-    equations: &[Vec<Fraction>],
-    pivots: &[(usize, usize)],
-    solution: &mut [Fraction],
-    best: &mut Option<usize>,
-) {
-    if free_index == free_cols.len() {
-        let rhs_col = machine.buttons.len();
-
-        for &(row, pivot_col) in pivots {
-            let mut value = equations[row][rhs_col];
-            for &free_col in free_cols {
-                value = value - equations[row][free_col] * solution[free_col];
-            }
-            solution[pivot_col] = value;
-        }
-
-        let mut presses = 0;
-        for value in solution.iter() {
-            let Some(count) = value.as_nonnegative_usize() else {
-                return;
-            };
-            presses += count;
-        }
-
+    system.for_each_nonnegative_integer_solution(&upper_bounds, |presses| {
+        let presses = presses.iter().sum::<u64>() as usize;
         if best.is_none_or(|best| presses < best) {
-            *best = Some(presses);
+            best = Some(presses);
         }
-        return;
-    }
-
-    let free_col = free_cols[free_index];
-    let max_count = machine.buttons[free_col]
-        .iter()
-        .map(|&slot| machine.joltage[slot])
-        .min()
-        .unwrap_or(0);
+    });
 
-    for count in 0..=max_count {
-        solution[free_col] = Fraction::new(count as i128, 1);
-        enumerate_free_variables(
-            free_index + 1,
-            free_cols,
-            machine,
-            equations,
-            pivots,
-            solution,
-            best,
-        );
-    }
-
-    solution[free_col] = Fraction::zero();
-}
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct Fraction {
-    num: i128,
-    den: i128,
-}
-
-impl Fraction {
-    fn new(mut num: i128, mut den: i128) -> Fraction {
-        if den == 0 {
-            panic!("divide by zero");
-        }
-
-        if den < 0 {
-            num = -num;
-            den = -den;
-        }
-        if num == 0 {
-            den = 1;
-        }
-        let n = gcd(num.abs(), den);
-        num /= n;
-        den /= n;
-        Self { num, den }
-    }
-
-    fn zero() -> Fraction {
-        Self { num: 0, den: 1 }
-    }
-
-    fn one() -> Fraction {
-        Self { num: 1, den: 1 }
-    }
-
-    fn is_zero(&self) -> bool {
-        self.num == 0
-    }
-
-    fn as_nonnegative_usize(&self) -> Option<usize> {
-        if self.den == 1 && self.num >= 0 {
-            Some(self.num as usize)
-        } else {
-            None
-        }
-    }
-}
-
-impl Add for Fraction {
-    type Output = Fraction;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
-    }
-}
-
-impl Sub for Fraction {
-    type Output = Fraction;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self::new(self.num * rhs.den - rhs.num * self.den, self.den * rhs.den)
-    }
-}
-
-impl Mul for Fraction {
-    type Output = Fraction;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self::new(self.num * rhs.num, self.den * rhs.den)
-    }
-}
-
-impl Div for Fraction {
-    type Output = Fraction;
-
-    fn div(self, rhs: Self) -> Self::Output {
-        Self::new(self.num * rhs.den, self.den * rhs.num)
-    }
+    best.expect("no solution found")
 }
 
 fn fewest_button_presses(machine: Machine) -> usize {
@@ -0,0 +1,377 @@
+// A small framework for the "here's a program in a made up assembly language" puzzles. A puzzle
+// only has to say how to parse an instruction and what it does to the machine's state, running it
+// (with loop detection, cycle counting and hooks on every cycle) is handled here.
+
+use std::fmt::Debug;
+
+use anyhow::Context;
+
+/// Where to go after executing an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// The next instruction.
+    Next,
+    /// Relative to the current instruction.
+    Jump(isize),
+    /// An absolute instruction index.
+    Goto(usize),
+    Halt,
+}
+
+/// An instruction set. `State` is whatever the instructions work on, e.g. the registers.
+pub trait Instruction: Sized + Debug {
+    type State;
+
+    /// Parses a single line of the program. Instruction sets without a text form build their
+    /// program themselves and use `Machine::new` instead.
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        anyhow::bail!("can't parse '{line}', there's no text form for these instructions")
+    }
+
+    fn execute(&self, state: &mut Self::State) -> Flow;
+
+    /// How many cycles the instruction takes. The state only changes at the end of the last one.
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    /// Where the instruction always goes, if that doesn't depend on the state. Needed for
+    /// `Machine::find_patch`.
+    fn static_flow(&self) -> Option<Flow> {
+        None
+    }
+}
+
+/// How a program stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Jumped outside the program or hit a halt instruction.
+    Halted,
+    /// Was about to run the instruction at this index a second time.
+    Loop(usize),
+}
+
+/// A machine with one instruction replaced so that it halts, see `Machine::find_patch`.
+pub struct Patch<I: Instruction> {
+    /// The index of the replaced instruction.
+    pub index: usize,
+    /// The patched machine, after running until it halted.
+    pub machine: Machine<I>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<I: Instruction> {
+    pub program: Vec<I>,
+    pub state: I::State,
+    pub ip: usize,
+    /// Number of cycles run so far.
+    pub cycle: usize,
+    halted: bool,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>, state: I::State) -> Self {
+        Self {
+            program,
+            state,
+            ip: 0,
+            cycle: 0,
+            halted: false,
+        }
+    }
+
+    /// Parses every non-empty line as an instruction.
+    pub fn parse(input: &str, state: I::State) -> anyhow::Result<Self> {
+        let program = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                I::parse(line.trim()).map_err(|e| e.context(format!("line {}: '{line}'", n + 1)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self::new(program, state))
+    }
+
+    /// Starts the program over from the first instruction with a new state, e.g. to try another
+    /// input without parsing the program again.
+    pub fn restart(&mut self, state: I::State) {
+        self.state = state;
+        self.ip = 0;
+        self.cycle = 0;
+        self.halted = false;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted || self.ip >= self.program.len()
+    }
+
+    /// Executes one instruction, calling `on_tick` with the cycle number (starting from 1) and the
+    /// state during that cycle for every cycle it takes. Returns false if the machine had already
+    /// halted.
+    pub fn step_with(&mut self, mut on_tick: impl FnMut(usize, &I::State)) -> bool {
+        if self.is_halted() {
+            return false;
+        }
+        let instruction = &self.program[self.ip];
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            on_tick(self.cycle, &self.state);
+        }
+        match instruction.execute(&mut self.state) {
+            Flow::Next => self.ip += 1,
+            // jumping before the start halts, same as jumping past the end
+            Flow::Jump(offset) => match self.ip.checked_add_signed(offset) {
+                Some(ip) => self.ip = ip,
+                None => self.halted = true,
+            },
+            Flow::Goto(ip) => self.ip = ip,
+            Flow::Halt => self.halted = true,
+        }
+        true
+    }
+
+    pub fn step(&mut self) -> bool {
+        self.step_with(|_, _| ())
+    }
+
+    /// Runs until the program halts, calling `on_tick` for every cycle.
+    pub fn run_with(&mut self, mut on_tick: impl FnMut(usize, &I::State)) {
+        while self.step_with(&mut on_tick) {}
+    }
+
+    /// Runs until the program halts.
+    pub fn run(&mut self) {
+        self.run_with(|_, _| ())
+    }
+
+    /// Runs until the program halts or is about to execute an instruction for the second time.
+    /// Only useful when jumps don't depend on the state, otherwise coming back to an instruction
+    /// doesn't mean it's stuck.
+    pub fn run_until_loop(&mut self) -> Exit {
+        let mut seen = vec![false; self.program.len()];
+        while !self.is_halted() {
+            if std::mem::replace(&mut seen[self.ip], true) {
+                return Exit::Loop(self.ip);
+            }
+            self.step();
+        }
+        Exit::Halted
+    }
+
+    // where the instruction at `ip` goes, `None` if that's out of the program
+    fn static_next(&self, ip: usize, instruction: &I) -> anyhow::Result<Option<usize>> {
+        let flow = instruction
+            .static_flow()
+            .with_context(|| format!("where {instruction:?} at {ip} goes depends on the state"))?;
+        let next = match flow {
+            Flow::Next => Some(ip + 1),
+            Flow::Jump(offset) => ip.checked_add_signed(offset),
+            Flow::Goto(ip) => Some(ip),
+            Flow::Halt => None,
+        };
+        Ok(next.filter(|&next| next < self.program.len()))
+    }
+}
+
+impl<I> Machine<I>
+where
+    I: Instruction + Clone,
+    I::State: Clone,
+{
+    /// Finds a single instruction to replace with one of its `mutations` so that the program halts
+    /// instead of looping forever, in time linear in the length of the program. Every instruction
+    /// has to have a `static_flow`.
+    ///
+    /// Works out which instructions lead to the end of the program by walking backwards from it.
+    /// The program gets stuck in a loop without ever touching one of those, so the patch has to be
+    /// an instruction on that loop (or on the way to it) that jumps to one of them instead. `None`
+    /// if there's no such instruction, or if the program already halts and doesn't need one.
+    pub fn find_patch(&self, mutations: impl Fn(&I) -> Vec<I>) -> anyhow::Result<Option<Patch<I>>> {
+        let len = self.program.len();
+        let mut preds = vec![Vec::new(); len + 1];
+        for (ip, instruction) in self.program.iter().enumerate() {
+            let next = self.static_next(ip, instruction)?.unwrap_or(len);
+            preds[next].push(ip);
+        }
+        let mut terminates = vec![false; len + 1];
+        terminates[len] = true;
+        let mut stack = vec![len];
+        while let Some(ip) = stack.pop() {
+            for &p in &preds[ip] {
+                if !std::mem::replace(&mut terminates[p], true) {
+                    stack.push(p);
+                }
+            }
+        }
+
+        if terminates.get(self.ip).copied().unwrap_or(true) {
+            return Ok(None);
+        }
+
+        let mut seen = vec![false; len];
+        let mut ip = self.ip;
+        while ip < len && !std::mem::replace(&mut seen[ip], true) {
+            for mutation in mutations(&self.program[ip]) {
+                let next = self.static_next(ip, &mutation)?.unwrap_or(len);
+                if terminates[next] {
+                    let mut machine = self.clone();
+                    machine.program[ip] = mutation;
+                    machine.run();
+                    return Ok(Some(Patch { index: ip, machine }));
+                }
+            }
+            ip = self.static_next(ip, &self.program[ip])?.unwrap_or(len);
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // counts down from a register, remembering every value it saw
+    #[derive(Debug, Clone)]
+    enum Op {
+        Dec,
+        Jnz(isize),
+        Halt,
+    }
+
+    impl Instruction for Op {
+        type State = (i64, Vec<i64>);
+
+        fn parse(line: &str) -> anyhow::Result<Self> {
+            Ok(match line.split_once(' ') {
+                None if line == "dec" => Op::Dec,
+                None if line == "halt" => Op::Halt,
+                Some(("jnz", offset)) => Op::Jnz(offset.parse()?),
+                _ => anyhow::bail!("unknown instruction"),
+            })
+        }
+
+        fn execute(&self, (n, seen): &mut Self::State) -> Flow {
+            match self {
+                Op::Dec => {
+                    *n -= 1;
+                    seen.push(*n);
+                    Flow::Next
+                }
+                Op::Jnz(offset) if *n != 0 => Flow::Jump(*offset),
+                Op::Jnz(_) => Flow::Next,
+                Op::Halt => Flow::Halt,
+            }
+        }
+
+        fn cycles(&self) -> usize {
+            match self {
+                Op::Jnz(_) => 2,
+                _ => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let mut machine = Machine::<Op>::parse("dec\njnz -1\nhalt\ndec", (3, vec![])).unwrap();
+        let mut ticks = vec![];
+        machine.run_with(|cycle, (n, _)| ticks.push((cycle, *n)));
+        assert!(machine.is_halted());
+        assert_eq!(2, machine.ip);
+        assert_eq!(vec![2, 1, 0], machine.state.1);
+        assert_eq!(10, machine.cycle);
+        assert_eq!(
+            vec![
+                (1, 3),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (5, 1),
+                (6, 1),
+                (7, 1),
+                (8, 0),
+                (9, 0),
+                (10, 0)
+            ],
+            ticks
+        );
+        assert!(!machine.step());
+
+        machine.restart((1, vec![]));
+        machine.run();
+        assert_eq!((0, vec![0]), machine.state);
+        assert_eq!(4, machine.cycle);
+    }
+
+    #[test]
+    fn test_loop() {
+        let mut machine = Machine::<Op>::parse("dec\njnz -1", (3, vec![])).unwrap();
+        assert_eq!(Exit::Loop(0), machine.run_until_loop());
+        assert_eq!(2, machine.state.0);
+
+        let mut machine = Machine::<Op>::parse("dec\njnz 5", (3, vec![])).unwrap();
+        assert_eq!(Exit::Halted, machine.run_until_loop());
+
+        let err = Machine::<Op>::parse("dec\n\njnz x", (0, vec![])).unwrap_err();
+        assert_eq!("line 3: 'jnz x'", err.to_string());
+    }
+
+    // jumps that don't depend on anything, counting how many instructions ran
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Hop {
+        Nop(isize),
+        Jmp(isize),
+    }
+
+    impl Instruction for Hop {
+        type State = usize;
+
+        fn parse(line: &str) -> anyhow::Result<Self> {
+            Ok(match line.split_once(' ') {
+                Some(("nop", offset)) => Hop::Nop(offset.parse()?),
+                Some(("jmp", offset)) => Hop::Jmp(offset.parse()?),
+                _ => anyhow::bail!("unknown instruction"),
+            })
+        }
+
+        fn execute(&self, count: &mut usize) -> Flow {
+            *count += 1;
+            self.static_flow().unwrap()
+        }
+
+        fn static_flow(&self) -> Option<Flow> {
+            Some(match self {
+                Hop::Nop(_) => Flow::Next,
+                Hop::Jmp(offset) => Flow::Jump(*offset),
+            })
+        }
+    }
+
+    fn flip(hop: &Hop) -> Vec<Hop> {
+        match *hop {
+            Hop::Nop(offset) => vec![Hop::Jmp(offset)],
+            Hop::Jmp(offset) => vec![Hop::Nop(offset)],
+        }
+    }
+
+    #[test]
+    fn test_find_patch() {
+        // 0 -> 2 -> 3 -> 4 -> 1 -> 2, the only way out is turning the nop at 3 into a jump past the end
+        let machine = Machine::<Hop>::parse("jmp 2\njmp 1\nnop -2\nnop 3\njmp -3", 0).unwrap();
+        assert_eq!(Exit::Loop(2), machine.clone().run_until_loop());
+        let patch = machine.find_patch(flip).unwrap().unwrap();
+        assert_eq!(3, patch.index);
+        assert_eq!(Hop::Jmp(3), patch.machine.program[3]);
+        assert!(patch.machine.is_halted());
+        assert_eq!(3, patch.machine.state);
+
+        let machine = Machine::<Hop>::parse("jmp 0", 0).unwrap();
+        assert!(machine.find_patch(|_| vec![]).unwrap().is_none());
+        // already halts, even though flipping the nop would too
+        let machine = Machine::<Hop>::parse("nop 2\njmp 1", 0).unwrap();
+        assert!(machine.find_patch(flip).unwrap().is_none());
+        let machine = Machine::<Op>::parse("jnz 0", (1, vec![])).unwrap();
+        assert!(machine.find_patch(|_| vec![]).is_err());
+    }
+}
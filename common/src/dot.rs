@@ -0,0 +1,173 @@
+// Graphviz output for looking at puzzle inputs, e.g. `cargo run -p aoc24 -- 24 --dot < input |
+// dot -Tsvg > day24.svg`.
+
+use std::fmt;
+
+use anyhow::Context;
+
+type Attrs = Vec<(&'static str, String)>;
+
+/// Something that can be drawn as a graph.
+pub trait ToDot {
+    fn to_dot(&self) -> Dot;
+}
+
+/// How to draw a day's input.
+pub type Graph = fn(&str) -> anyhow::Result<Dot>;
+
+/// The runners' list of days, e.g. `days![day01, day02(dot), day03]`. Gives each day's `run`
+/// and, lined up with them, the `dot` of every day marked `(dot)` or `None` for the rest, so the
+/// graphs can't get out of step with the days.
+#[macro_export]
+macro_rules! days {
+    ($($day:ident $(($dot:ident))?),* $(,)?) => {
+        (
+            [$($day::run),*],
+            [$($crate::days!(@graph $day $($dot)?)),*],
+        )
+    };
+    (@graph $day:ident dot) => {
+        Some($day::dot as $crate::dot::Graph)
+    };
+    (@graph $day:ident) => {
+        None::<$crate::dot::Graph>
+    };
+}
+
+/// Prints day `day` (counting from 1) of `graphs` from `days!`, failing if it doesn't have one.
+pub fn print_graph(graphs: &[Option<Graph>], day: usize, input: &str) -> anyhow::Result<()> {
+    let graph = graphs
+        .get(day.wrapping_sub(1))
+        .copied()
+        .flatten()
+        .with_context(|| format!("Day {day} has no graph"))?;
+    print!("{}", graph(input)?);
+    Ok(())
+}
+
+/// A graphviz graph. Nodes and edges are written in the order they were added, and a node only
+/// needs to be added explicitly if it has attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dot {
+    directed: bool,
+    nodes: Vec<(String, Attrs)>,
+    edges: Vec<(String, String, Attrs)>,
+}
+
+impl Dot {
+    pub fn digraph() -> Self {
+        Self {
+            directed: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn graph() -> Self {
+        Self::default()
+    }
+
+    pub fn node(&mut self, id: impl Into<String>, attrs: Attrs) -> &mut Self {
+        self.nodes.push((id.into(), attrs));
+        self
+    }
+
+    pub fn edge(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        attrs: Attrs,
+    ) -> &mut Self {
+        self.edges.push((from.into(), to.into(), attrs));
+        self
+    }
+}
+
+// ids and values are always quoted so they can contain anything
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_attrs(f: &mut fmt::Formatter<'_>, attrs: &Attrs) -> fmt::Result {
+    if attrs.is_empty() {
+        return Ok(());
+    }
+    let attrs: Vec<String> = attrs
+        .iter()
+        .map(|(k, v)| format!("{k}={}", quote(v)))
+        .collect();
+    write!(f, " [{}]", attrs.join(", "))
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        writeln!(f, "{kind} {{")?;
+        for (id, attrs) in &self.nodes {
+            write!(f, "  {}", quote(id))?;
+            write_attrs(f, attrs)?;
+            writeln!(f, ";")?;
+        }
+        for (from, to, attrs) in &self.edges {
+            write!(f, "  {} {arrow} {}", quote(from), quote(to))?;
+            write_attrs(f, attrs)?;
+            writeln!(f, ";")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut dot = Dot::digraph();
+        dot.node("z00", vec![("color", "red".to_string())])
+            .edge("x00", "z00", vec![])
+            .edge("y00", "z00", vec![("label", "say \"hi\"".to_string())]);
+        assert_eq!(
+            "digraph {\n  \"z00\" [color=\"red\"];\n  \"x00\" -> \"z00\";\n  \"y00\" -> \"z00\" [label=\"say \\\"hi\\\"\"];\n}\n",
+            dot.to_string()
+        );
+
+        let mut dot = Dot::graph();
+        dot.edge("a", "b", vec![]);
+        assert_eq!("graph {\n  \"a\" -- \"b\";\n}\n", dot.to_string());
+    }
+
+    mod day01 {
+        pub fn run(input: &str) -> usize {
+            input.len()
+        }
+    }
+
+    mod day02 {
+        pub fn run(input: &str) -> usize {
+            input.lines().count()
+        }
+
+        pub fn dot(input: &str) -> anyhow::Result<super::Dot> {
+            let mut dot = super::Dot::graph();
+            dot.node(input, vec![]);
+            Ok(dot)
+        }
+    }
+
+    #[test]
+    fn test_days() {
+        let (days, graphs) = crate::days![day01, day02(dot)];
+        assert_eq!([3, 2], days.map(|run| run("a\nb")));
+        assert!(graphs[0].is_none());
+        assert_eq!(
+            "graph {\n  \"a\";\n}\n",
+            graphs[1].unwrap()("a").unwrap().to_string()
+        );
+        assert!(print_graph(&graphs, 1, "a").is_err());
+        assert!(print_graph(&graphs, 3, "a").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use num::{One, Zero, integer::gcd};
+
+/// An exact rational number over i128, always stored in lowest terms with a positive
+/// denominator.
+///
+/// Every operation is checked, so an intermediate value that doesn't fit in an i128 panics instead
+/// of silently wrapping. Use `num::BigRational` (which works with `linalg` too) for inputs that
+/// need more room than that.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    num: i128,
+    den: i128,
+}
+
+impl Fraction {
+    pub fn new(mut num: i128, mut den: i128) -> Fraction {
+        if den == 0 {
+            panic!("divide by zero");
+        }
+
+        if den < 0 {
+            num = checked(num.checked_neg());
+            den = checked(den.checked_neg());
+        }
+        if num == 0 {
+            den = 1;
+        }
+        let n = gcd(num, den);
+        num /= n;
+        den /= n;
+        Self { num, den }
+    }
+
+    pub fn from_integer(n: i128) -> Fraction {
+        Self { num: n, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /// Returns the value as an integer, or `None` if it has a fractional part.
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn as_nonnegative_usize(&self) -> Option<usize> {
+        self.to_integer()
+            .filter(|n| *n >= 0)
+            .and_then(|n| usize::try_from(n).ok())
+    }
+
+    pub fn recip(&self) -> Fraction {
+        Self::new(self.den, self.num)
+    }
+
+    pub fn abs(&self) -> Fraction {
+        Self {
+            num: checked(self.num.checked_abs()),
+            den: self.den,
+        }
+    }
+}
+
+fn checked(n: Option<i128>) -> i128 {
+    n.expect("fraction overflowed i128")
+}
+
+impl Zero for Fraction {
+    fn zero() -> Fraction {
+        Self { num: 0, den: 1 }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+}
+
+impl One for Fraction {
+    fn one() -> Fraction {
+        Self { num: 1, den: 1 }
+    }
+}
+
+impl From<i64> for Fraction {
+    fn from(n: i64) -> Self {
+        Self::from_integer(n as i128)
+    }
+}
+
+impl From<i128> for Fraction {
+    fn from(n: i128) -> Self {
+        Self::from_integer(n)
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // scale both sides up to the lcm of the denominators instead of their product to keep the
+        // intermediate values small
+        let g = gcd(self.den, rhs.den);
+        let lhs_scale = rhs.den / g;
+        let rhs_scale = self.den / g;
+        let num = checked(
+            checked(self.num.checked_mul(lhs_scale))
+                .checked_add(checked(rhs.num.checked_mul(rhs_scale))),
+        );
+        Self::new(num, checked(self.den.checked_mul(lhs_scale)))
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // cancel across the fractions before multiplying to keep the intermediate values small
+        let g1 = gcd(self.num, rhs.den);
+        let g2 = gcd(rhs.num, self.den);
+        Self::new(
+            checked((self.num / g1).checked_mul(rhs.num / g2)),
+            checked((self.den / g2).checked_mul(rhs.den / g1)),
+        )
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.recip()
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: checked(self.num.checked_neg()),
+            den: self.den,
+        }
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are always positive so cross multiplying preserves the ordering
+        let lhs = checked(self.num.checked_mul(other.den));
+        let rhs = checked(other.num.checked_mul(self.den));
+        lhs.cmp(&rhs)
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Fraction::new(1, 2), Fraction::new(-3, -6));
+        assert_eq!(Fraction::new(-1, 2), Fraction::new(3, -6));
+        assert_eq!(Fraction::zero(), Fraction::new(0, -7));
+        assert_eq!("-1/2", Fraction::new(2, -4).to_string());
+        assert_eq!("5", Fraction::new(10, 2).to_string());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Fraction::new(1, 6);
+        let b = Fraction::new(3, 4);
+        assert_eq!(Fraction::new(11, 12), a + b);
+        assert_eq!(Fraction::new(-7, 12), a - b);
+        assert_eq!(Fraction::new(1, 8), a * b);
+        assert_eq!(Fraction::new(2, 9), a / b);
+        assert!(a < b);
+        assert!(-b < -a);
+    }
+
+    #[test]
+    fn test_large_values_reduce_before_overflow() {
+        // the naive product of the numerators and denominators would overflow an i128
+        let big = Fraction::new(i128::MAX / 3, 7);
+        assert_eq!(Fraction::from(3i64), big * Fraction::new(21, i128::MAX / 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn test_overflow_panics() {
+        let _ = Fraction::from_integer(i128::MAX) + Fraction::one();
+    }
+}
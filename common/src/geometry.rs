@@ -0,0 +1,308 @@
+// 2D geometry on integer lattice points. Polygons are given as their vertices in order (either
+// direction) and are implicitly closed, the last vertex joins back up to the first.
+
+use std::collections::VecDeque;
+
+use num::integer::gcd;
+
+pub type Point = (i64, i64);
+
+/// Twice the area of a simple polygon, using the shoelace formula. Doubling keeps it an integer.
+pub fn double_area(polygon: &[Point]) -> u64 {
+    edges(polygon)
+        .map(|(a, b)| a.0 as i128 * b.1 as i128 - a.1 as i128 * b.0 as i128)
+        .sum::<i128>()
+        .unsigned_abs() as u64
+}
+
+/// Number of lattice points on the edges of the polygon.
+pub fn boundary_points(polygon: &[Point]) -> u64 {
+    edges(polygon)
+        .map(|(a, b)| gcd(b.0 - a.0, b.1 - a.1).unsigned_abs())
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon, from Pick's theorem `A = I + B/2 - 1`.
+pub fn interior_points(polygon: &[Point]) -> u64 {
+    if polygon.len() < 3 {
+        return 0;
+    }
+    // a degenerate polygon (all its vertices on a line) has more boundary points than Pick's
+    // theorem allows for, and nothing inside
+    (double_area(polygon) + 2).saturating_sub(boundary_points(polygon)) / 2
+}
+
+/// Number of lattice points inside or on the edges of the polygon.
+pub fn lattice_points(polygon: &[Point]) -> u64 {
+    interior_points(polygon) + boundary_points(polygon)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Where `p` is relative to the polygon, by counting how many edges a ray from `p` crosses.
+pub fn locate(polygon: &[Point], p: Point) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        let cross =
+            (b.0 - a.0) as i128 * (p.1 - a.1) as i128 - (b.1 - a.1) as i128 * (p.0 - a.0) as i128;
+        let within = a.0.min(b.0) <= p.0
+            && p.0 <= a.0.max(b.0)
+            && a.1.min(b.1) <= p.1
+            && p.1 <= a.1.max(b.1);
+        if cross == 0 && within {
+            return Location::Boundary;
+        }
+        // does the edge cross the ray going from p towards +x? Counting an endpoint only for the
+        // edge going up from it means a vertex on the ray is counted once.
+        if (a.1 > p.1) != (b.1 > p.1) {
+            let crosses_right = if b.1 > a.1 { cross > 0 } else { cross < 0 };
+            if crosses_right {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Maps a sparse set of coordinates onto `0..len()` while keeping their order.
+#[derive(Debug, Clone)]
+pub struct CompressedAxis {
+    values: Vec<i64>,
+}
+
+impl CompressedAxis {
+    pub fn new(values: impl IntoIterator<Item = i64>) -> Self {
+        let mut values: Vec<i64> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        Self { values }
+    }
+
+    /// The index of `value`, if it is one of the coordinates.
+    pub fn index(&self, value: i64) -> Option<usize> {
+        self.values.binary_search(&value).ok()
+    }
+
+    /// The index of the largest coordinate that is at most `value`, i.e. the band
+    /// `value(i)..value(i + 1)` that `value` falls in.
+    pub fn band(&self, value: i64) -> Option<usize> {
+        self.values.partition_point(|v| *v <= value).checked_sub(1)
+    }
+
+    pub fn value(&self, index: usize) -> i64 {
+        self.values[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// A polygon whose edges are all horizontal or vertical, preprocessed so asking whether a
+/// rectangle fits inside it is O(1).
+///
+/// The plane is cut into bands at every vertex coordinate `v` and at `v + 1`, so every band is
+/// either a single line of lattice points that edges can lie on, or a run of them that no vertex
+/// lines up with. Every lattice point in a cell (a pair of bands) is then in the same place
+/// relative to the polygon, so only the cells need checking.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon {
+    xs: CompressedAxis,
+    ys: CompressedAxis,
+    // outside[i][j] is the number of outside cells in bands 0..i of x and 0..j of y
+    outside: Vec<Vec<u32>>,
+}
+
+impl RectilinearPolygon {
+    pub fn new(polygon: &[Point]) -> Self {
+        assert!(
+            edges(polygon).all(|(a, b)| a.0 == b.0 || a.1 == b.1),
+            "polygon is not rectilinear"
+        );
+        let axis = |coord: fn(&Point) -> i64| {
+            let min = polygon.iter().map(coord).min().unwrap_or(0);
+            // the band before the first vertex is outside and gives the flood fill a way round
+            CompressedAxis::new(
+                polygon
+                    .iter()
+                    .map(coord)
+                    .flat_map(|v| [v, v + 1])
+                    .chain([min - 1]),
+            )
+        };
+        let (xs, ys) = (axis(|p| p.0), axis(|p| p.1));
+        let (w, h) = (xs.len(), ys.len());
+
+        let mut edge = vec![vec![false; h]; w];
+        for (a, b) in edges(polygon) {
+            let (x0, x1) = (
+                xs.index(a.0.min(b.0)).unwrap(),
+                xs.index(a.0.max(b.0)).unwrap(),
+            );
+            let (y0, y1) = (
+                ys.index(a.1.min(b.1)).unwrap(),
+                ys.index(a.1.max(b.1)).unwrap(),
+            );
+            for col in &mut edge[x0..=x1] {
+                col[y0..=y1].fill(true);
+            }
+        }
+
+        // flood fill the outside starting from the first band, which nothing can reach
+        let mut is_outside = vec![vec![false; h]; w];
+        is_outside[0][0] = true;
+        let mut queue = VecDeque::from([(0usize, 0usize)]);
+        while let Some((x, y)) = queue.pop_front() {
+            let next = [
+                (x + 1, y),
+                (x.wrapping_sub(1), y),
+                (x, y + 1),
+                (x, y.wrapping_sub(1)),
+            ];
+            for (nx, ny) in next {
+                if nx < w && ny < h && !edge[nx][ny] && !is_outside[nx][ny] {
+                    is_outside[nx][ny] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        let mut outside = vec![vec![0; h + 1]; w + 1];
+        for x in 0..w {
+            for y in 0..h {
+                outside[x + 1][y + 1] =
+                    outside[x][y + 1] + outside[x + 1][y] - outside[x][y] + is_outside[x][y] as u32;
+            }
+        }
+        Self { xs, ys, outside }
+    }
+
+    /// Whether every lattice point in the rectangle with opposite corners `a` and `b` is inside or
+    /// on the edge of the polygon.
+    pub fn contains_rect(&self, a: Point, b: Point) -> bool {
+        let x = self.xs.band(a.0.min(b.0)).zip(self.xs.band(a.0.max(b.0)));
+        let y = self.ys.band(a.1.min(b.1)).zip(self.ys.band(a.1.max(b.1)));
+        let (Some((x0, x1)), Some((y0, y1))) = (x, y) else {
+            return false;
+        };
+        let o = &self.outside;
+        o[x1 + 1][y1 + 1] + o[x0][y0] - o[x0][y1 + 1] - o[x1 + 1][y0] == 0
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.contains_rect(p, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 6x4 block with a 2 wide notch cut out of the top
+    //
+    //   ##..##
+    //   ##..##
+    //   ######
+    //   ######
+    const NOTCHED: [Point; 8] = [
+        (0, 0),
+        (5, 0),
+        (5, 3),
+        (4, 3),
+        (4, 2),
+        (1, 2),
+        (1, 3),
+        (0, 3),
+    ];
+
+    #[test]
+    fn test_pick() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(32, double_area(&square));
+        assert_eq!(16, boundary_points(&square));
+        assert_eq!(9, interior_points(&square));
+        assert_eq!(25, lattice_points(&square));
+
+        let triangle = [(0, 0), (4, 0), (0, 4)];
+        assert_eq!(16, double_area(&triangle));
+        assert_eq!(12, boundary_points(&triangle));
+        assert_eq!(3, interior_points(&triangle));
+
+        assert_eq!(24, double_area(&NOTCHED));
+        assert_eq!(18, boundary_points(&NOTCHED));
+        assert_eq!(4, interior_points(&NOTCHED));
+        assert_eq!(22, lattice_points(&NOTCHED));
+
+        let collinear = [(0, 0), (2, 0), (1, 0)];
+        assert_eq!(0, double_area(&collinear));
+        assert_eq!(4, boundary_points(&collinear));
+        assert_eq!(0, interior_points(&collinear));
+    }
+
+    #[test]
+    fn test_locate() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(Location::Inside, locate(&square, (2, 2)));
+        assert_eq!(Location::Boundary, locate(&square, (4, 1)));
+        assert_eq!(Location::Boundary, locate(&square, (0, 0)));
+        assert_eq!(Location::Outside, locate(&square, (5, 2)));
+        assert_eq!(Location::Outside, locate(&square, (-1, 4)));
+
+        assert_eq!(Location::Outside, locate(&NOTCHED, (2, 3)));
+        assert_eq!(Location::Boundary, locate(&NOTCHED, (2, 2)));
+        assert_eq!(Location::Inside, locate(&NOTCHED, (3, 1)));
+        // the ray from here passes through two vertices of the notch
+        assert_eq!(Location::Outside, locate(&NOTCHED, (-1, 2)));
+    }
+
+    #[test]
+    fn test_rectilinear() {
+        let polygon = RectilinearPolygon::new(&NOTCHED);
+        assert!(polygon.contains_rect((0, 0), (5, 2)));
+        assert!(polygon.contains_rect((0, 3), (1, 0)));
+        assert!(polygon.contains_rect((4, 3), (5, 3)));
+        assert!(!polygon.contains_rect((0, 0), (5, 3)));
+        assert!(!polygon.contains_rect((1, 3), (4, 3)));
+        assert!(polygon.contains((3, 2)));
+        assert!(!polygon.contains((2, 3)));
+        assert!(!polygon.contains((6, 0)));
+        assert!(!polygon.contains((-1, 0)));
+
+        // matches checking every point one at a time
+        for (x, y) in (-1..=6).flat_map(|x| (-1..=4).map(move |y| (x, y))) {
+            let expected = locate(&NOTCHED, (x, y)) != Location::Outside;
+            assert_eq!(expected, polygon.contains((x, y)), "{x},{y}");
+        }
+    }
+
+    #[test]
+    fn test_compressed_axis() {
+        let axis = CompressedAxis::new([10, -5, 3, 10]);
+        assert_eq!(3, axis.len());
+        assert_eq!(Some(2), axis.index(10));
+        assert_eq!(None, axis.index(4));
+        assert_eq!(Some(1), axis.band(4));
+        assert_eq!(None, axis.band(-6));
+        assert_eq!(3, axis.value(1));
+    }
+}
@@ -0,0 +1,585 @@
+// General purpose graph algorithms. Nodes are plain indices (`0..num_nodes`), `Graph` takes care
+// of mapping a puzzle's names onto those indices.
+
+use std::{cell::OnceCell, collections::VecDeque, hash::Hash};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+/// A graph over nodes named by `K` (usually the `&str` names straight from the input) with edges
+/// weighted by `W`. Every name is interned to an index the first time it is seen, and the
+/// algorithms all work on those indices.
+#[derive(Debug, Clone)]
+pub struct Graph<K, W = ()> {
+    directed: bool,
+    ids: FxHashMap<K, usize>,
+    names: Vec<K>,
+    adj: Vec<Vec<(usize, W)>>,
+    edges: Vec<(usize, usize, W)>,
+    // neighbours ignoring direction, built the first time a clique search needs them
+    adj_sets: OnceCell<Vec<FxHashSet<usize>>>,
+}
+
+impl<K: Hash + Eq + Clone, W: Clone> Graph<K, W> {
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            ids: FxHashMap::default(),
+            names: Vec::new(),
+            adj: Vec::new(),
+            edges: Vec::new(),
+            adj_sets: OnceCell::new(),
+        }
+    }
+
+    /// The index of `name`, adding it if it is new.
+    pub fn add_node(&mut self, name: K) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        self.adj.push(Vec::new());
+        self.adj_sets.take();
+        id
+    }
+
+    /// Adds an edge, and its endpoints if they are new. Returns the endpoints' indices.
+    pub fn add_weighted_edge(&mut self, from: K, to: K, weight: W) -> (usize, usize) {
+        let (a, b) = (self.add_node(from), self.add_node(to));
+        self.adj[a].push((b, weight.clone()));
+        if !self.directed && a != b {
+            self.adj[b].push((a, weight.clone()));
+        }
+        self.edges.push((a, b, weight));
+        self.adj_sets.take();
+        (a, b)
+    }
+
+    pub fn add_edge(&mut self, from: K, to: K) -> (usize, usize)
+    where
+        W: Default,
+    {
+        self.add_weighted_edge(from, to, W::default())
+    }
+
+    pub fn id(&self, name: &K) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &K {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The nodes an edge leads to from `id`.
+    pub fn neighbours(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adj[id].iter().map(|(to, _)| *to)
+    }
+
+    /// `(to, weight)` for every edge leaving `id`.
+    pub fn out_edges(&self, id: usize) -> &[(usize, W)] {
+        &self.adj[id]
+    }
+
+    /// Every edge once, in the order they were added.
+    pub fn edges(&self) -> &[(usize, usize, W)] {
+        &self.edges
+    }
+
+    /// Every node that can be reached from `start`, not counting `start` itself unless it is on a
+    /// cycle.
+    pub fn reachable(&self, start: usize) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        let mut res = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for next in self.neighbours(node) {
+                if !seen[next] {
+                    seen[next] = true;
+                    res.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        res
+    }
+
+    /// The connected components, ignoring edge directions.
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let mut undirected = vec![vec![]; self.len()];
+        for &(a, b, _) in &self.edges {
+            undirected[a].push(b);
+            undirected[b].push(a);
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut component = vec![start];
+            let mut idx = 0;
+            while idx < component.len() {
+                for &next in &undirected[component[idx]] {
+                    if !seen[next] {
+                        seen[next] = true;
+                        component.push(next);
+                    }
+                }
+                idx += 1;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Tarjan's strongly connected components. Components come out in reverse topological order,
+    /// i.e. nothing in a component has an edge to a component listed after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+        let n = self.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // (node, how many of its edges have been followed), standing in for recursion
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (node, ref mut edge)) = calls.last_mut() {
+                if let Some(&(next, _)) = self.adj[node].get(*edge) {
+                    *edge += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        low[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        low[node] = low[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if low[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// A largest set of nodes that are all connected to each other, found with Bron-Kerbosch.
+    /// Edge directions are ignored.
+    pub fn max_clique(&self) -> Vec<usize> {
+        let adj = self.adjacency_sets();
+        let mut max = Vec::new();
+        bron_kerbosch(
+            adj,
+            &mut Vec::new(),
+            (0..self.len()).collect(),
+            FxHashSet::default(),
+            &mut max,
+        );
+        max.sort();
+        max
+    }
+
+    /// Every set of `k` nodes that includes `node` and are all connected to each other, each
+    /// sorted by index. Edge directions are ignored.
+    pub fn cliques_containing(&self, node: usize, k: usize) -> Vec<Vec<usize>> {
+        let adj = self.adjacency_sets();
+        let mut candidates: Vec<usize> = adj[node].iter().copied().collect();
+        candidates.sort();
+
+        let mut res = Vec::new();
+        if k > 0 {
+            grow_clique(adj, &mut vec![node], &candidates, k, &mut res);
+        }
+        for clique in &mut res {
+            clique.sort();
+        }
+        res
+    }
+
+    fn adjacency_sets(&self) -> &[FxHashSet<usize>] {
+        self.adj_sets.get_or_init(|| {
+            let mut adj = vec![FxHashSet::default(); self.len()];
+            for &(a, b, _) in &self.edges {
+                if a != b {
+                    adj[a].insert(b);
+                    adj[b].insert(a);
+                }
+            }
+            adj
+        })
+    }
+}
+
+// r is the clique so far, p the nodes that could extend it and x the nodes that could extend it
+// but have already been tried
+fn bron_kerbosch(
+    adj: &[FxHashSet<usize>],
+    r: &mut Vec<usize>,
+    mut p: FxHashSet<usize>,
+    mut x: FxHashSet<usize>,
+    max: &mut Vec<usize>,
+) {
+    if p.is_empty() && x.is_empty() {
+        if r.len() > max.len() {
+            *max = r.clone();
+        }
+        return;
+    }
+    // a clique that can't grow past the best one so far isn't interesting
+    if r.len() + p.len() <= max.len() {
+        return;
+    }
+
+    // every maximal clique includes the pivot or one of its non-neighbours
+    let pivot = *p.union(&x).max_by_key(|v| adj[**v].len()).unwrap();
+    let candidates: Vec<usize> = p.difference(&adj[pivot]).copied().collect();
+    for v in candidates {
+        r.push(v);
+        let pv = p.intersection(&adj[v]).copied().collect();
+        let xv = x.intersection(&adj[v]).copied().collect();
+        bron_kerbosch(adj, r, pv, xv, max);
+        r.pop();
+        p.remove(&v);
+        x.insert(v);
+    }
+}
+
+// extends the clique with candidates in increasing order so every clique is only found once
+fn grow_clique(
+    adj: &[FxHashSet<usize>],
+    clique: &mut Vec<usize>,
+    candidates: &[usize],
+    k: usize,
+    res: &mut Vec<Vec<usize>>,
+) {
+    if clique.len() == k {
+        res.push(clique.clone());
+        return;
+    }
+    for (i, &c) in candidates.iter().enumerate() {
+        let rest: Vec<usize> = candidates[i + 1..]
+            .iter()
+            .copied()
+            .filter(|n| adj[c].contains(n))
+            .collect();
+        clique.push(c);
+        grow_clique(adj, clique, &rest, k, res);
+        clique.pop();
+    }
+}
+
+/// The smallest set of edges that splits a graph in two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// `side[n]` is true if node `n` is on the same side of the cut as node 0.
+    pub side: Vec<bool>,
+    /// The edges that cross the cut.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl MinCut {
+    pub fn size(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The number of nodes on each side of the cut.
+    pub fn partition_sizes(&self) -> (usize, usize) {
+        let first = self.side.iter().filter(|s| **s).count();
+        (first, self.side.len() - first)
+    }
+}
+
+/// Finds a global minimum edge cut of an undirected, unweighted graph, or `None` if the graph has
+/// fewer than two nodes.
+///
+/// Every cut separates node 0 from some other node `t`, and the smallest cut between two nodes is
+/// the number of edge disjoint paths between them (max-flow min-cut). So the global minimum is the
+/// smallest max flow from node 0 to every other node. Each flow only has to be pushed as far as
+/// the best cut so far which keeps this fast when the cut is small, as it is in the puzzles.
+pub fn min_cut(num_nodes: usize, edges: &[(usize, usize)]) -> Option<MinCut> {
+    if num_nodes < 2 {
+        return None;
+    }
+
+    let mut adj = vec![vec![]; num_nodes];
+    for (idx, &(a, b)) in edges.iter().enumerate() {
+        adj[a].push((b, idx));
+        adj[b].push((a, idx));
+    }
+
+    let mut best: Option<(usize, Vec<bool>)> = None;
+    for sink in 1..num_nodes {
+        let limit = best.as_ref().map_or(usize::MAX, |(size, _)| *size);
+        if let Some((flow, side)) = max_flow(&adj, edges, 0, sink, limit) {
+            best = Some((flow, side));
+        }
+    }
+
+    let (_, side) = best?;
+    let cut_edges = edges
+        .iter()
+        .copied()
+        .filter(|&(a, b)| side[a] != side[b])
+        .collect();
+    Some(MinCut {
+        side,
+        edges: cut_edges,
+    })
+}
+
+// Pushes unit flows from source to sink until no more fit. Returns the flow and the nodes still
+// reachable from the source (i.e. the source side of the cut), or `None` if the flow reached
+// `limit` first.
+fn max_flow(
+    adj: &[Vec<(usize, usize)>],
+    edges: &[(usize, usize)],
+    source: usize,
+    sink: usize,
+    limit: usize,
+) -> Option<(usize, Vec<bool>)> {
+    // flow along edges[e] from .0 to .1 is positive, negative is the other way
+    let mut flow = vec![0i8; edges.len()];
+    let mut total = 0;
+    loop {
+        if total >= limit {
+            return None;
+        }
+
+        // breadth first search through the residual graph remembering how we got to each node
+        let mut came_from: Vec<Option<(usize, usize)>> = vec![None; adj.len()];
+        let mut seen = vec![false; adj.len()];
+        seen[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            if node == sink {
+                break;
+            }
+            for &(next, e) in &adj[node] {
+                let forward = edges[e].0 == node;
+                let residual = if forward { flow[e] < 1 } else { flow[e] > -1 };
+                if residual && !seen[next] {
+                    seen[next] = true;
+                    came_from[next] = Some((node, e));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if !seen[sink] {
+            return Some((total, seen));
+        }
+
+        let mut node = sink;
+        while let Some((prev, e)) = came_from[node] {
+            flow[e] += if edges[e].0 == prev { 1 } else { -1 };
+            node = prev;
+        }
+        total += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024 day23 sample
+    const LAN: &str = "\
+kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn";
+
+    fn lan() -> Graph<&'static str> {
+        let mut graph = Graph::undirected();
+        for line in LAN.lines() {
+            let (a, b) = line.split_once('-').unwrap();
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    fn names<'a>(graph: &Graph<&'a str>, ids: &[usize]) -> Vec<&'a str> {
+        let mut names: Vec<_> = ids.iter().map(|&id| *graph.name(id)).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_max_clique() {
+        let graph = lan();
+        assert_eq!(
+            vec!["co", "de", "ka", "ta"],
+            names(&graph, &graph.max_clique())
+        );
+    }
+
+    #[test]
+    fn test_cliques_containing() {
+        let graph = lan();
+        let triangles = |name| -> Vec<Vec<&str>> {
+            let mut res: Vec<_> = graph
+                .cliques_containing(graph.id(&name).unwrap(), 3)
+                .iter()
+                .map(|c| names(&graph, c))
+                .collect();
+            res.sort();
+            res
+        };
+        assert_eq!(vec![vec!["tc", "td", "wh"]], triangles("tc"));
+        assert_eq!(
+            vec![
+                vec!["qp", "td", "wh"],
+                vec!["tc", "td", "wh"],
+                vec!["td", "wh", "yn"]
+            ],
+            triangles("wh")
+        );
+
+        let ta = graph.id(&"ta").unwrap();
+        let quads = graph.cliques_containing(ta, 4);
+        assert_eq!(
+            vec![vec!["co", "de", "ka", "ta"]],
+            vec![names(&graph, &quads[0])]
+        );
+        assert_eq!(1, quads.len());
+        assert_eq!(vec![vec![ta]], graph.cliques_containing(ta, 1));
+
+        // edges added after a search are seen by the next one
+        let mut graph = lan();
+        let tc = graph.id(&"tc").unwrap();
+        assert_eq!(1, graph.cliques_containing(tc, 3).len());
+        graph.add_edge("tc", "qp");
+        assert_eq!(4, graph.cliques_containing(tc, 3).len());
+    }
+
+    #[test]
+    fn test_components() {
+        let mut graph: Graph<char> = Graph::directed();
+        for (a, b) in [('a', 'b'), ('b', 'c'), ('c', 'a'), ('c', 'd'), ('e', 'f')] {
+            graph.add_edge(a, b);
+        }
+        let ids = |names: &str| -> Vec<usize> {
+            let mut ids: Vec<_> = names.chars().map(|c| graph.id(&c).unwrap()).collect();
+            ids.sort();
+            ids
+        };
+
+        let mut components = graph.connected_components();
+        components.iter_mut().for_each(|c| c.sort());
+        assert_eq!(vec![ids("abcd"), ids("ef")], components);
+
+        let mut sccs = graph.strongly_connected_components();
+        sccs.iter_mut().for_each(|c| c.sort());
+        // d comes before abc as nothing in d leads back to abc
+        assert_eq!(vec![ids("d"), ids("abc"), ids("f"), ids("e")], sccs);
+
+        let mut reachable = graph.reachable(graph.id(&'b').unwrap());
+        reachable.sort();
+        assert_eq!(ids("abcd"), reachable);
+    }
+
+    #[test]
+    fn test_min_cut() {
+        // two triangles joined by a single edge
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        let cut = min_cut(6, &edges).unwrap();
+        assert_eq!(vec![(2, 3)], cut.edges);
+        assert_eq!((3, 3), cut.partition_sizes());
+        assert!(cut.side[0] && cut.side[1] && cut.side[2]);
+    }
+
+    #[test]
+    fn test_min_cut_disconnected() {
+        let cut = min_cut(4, &[(0, 1), (2, 3)]).unwrap();
+        assert_eq!(0, cut.size());
+        assert_eq!((2, 2), cut.partition_sizes());
+        assert_eq!(None, min_cut(1, &[]));
+    }
+
+    #[test]
+    fn test_min_cut_complete() {
+        // every cut of K5 isolates at least one node, which takes 4 edges
+        let edges: Vec<_> = (0..5)
+            .flat_map(|a| (a + 1..5).map(move |b| (a, b)))
+            .collect();
+        let cut = min_cut(5, &edges).unwrap();
+        assert_eq!(4, cut.size());
+    }
+}
@@ -0,0 +1,163 @@
+// An N-dimensional axis-aligned box. Useful for puzzles that carve up a large space into ranges
+// (e.g. 2023 day19 workflow ranges, 2023 day22 bricks) where the individual points are too
+// numerous to track.
+
+/// An axis-aligned box in N dimensions. Every axis is the half-open range `[lo, hi)`, so a box
+/// with `lo == hi` on any axis is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperRect<const N: usize> {
+    pub lo: [i64; N],
+    pub hi: [i64; N],
+}
+
+impl<const N: usize> HyperRect<N> {
+    pub fn new(lo: [i64; N], hi: [i64; N]) -> Self {
+        Self { lo, hi }
+    }
+
+    /// Creates a box from inclusive bounds on every axis, which is how most puzzle inputs
+    /// describe them (e.g. `x=10..12`).
+    pub fn from_inclusive(lo: [i64; N], hi: [i64; N]) -> Self {
+        Self {
+            lo,
+            hi: hi.map(|h| h + 1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|axis| self.lo[axis] >= self.hi[axis])
+    }
+
+    /// Number of integer points contained in the box.
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..N)
+            .map(|axis| (self.hi[axis] - self.lo[axis]) as u64)
+            .product()
+    }
+
+    pub fn contains(&self, point: &[i64; N]) -> bool {
+        (0..N).all(|axis| self.lo[axis] <= point[axis] && point[axis] < self.hi[axis])
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The box shared by both `self` and `other`, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut res = *self;
+        for axis in 0..N {
+            res.lo[axis] = self.lo[axis].max(other.lo[axis]);
+            res.hi[axis] = self.hi[axis].min(other.hi[axis]);
+        }
+        if res.is_empty() { None } else { Some(res) }
+    }
+
+    /// Cuts the box with the plane `axis == at`. The first box holds everything below the plane
+    /// and the second holds everything at or above it. Either side is `None` if it would be
+    /// empty.
+    pub fn split(&self, axis: usize, at: i64) -> (Option<Self>, Option<Self>) {
+        let mut below = *self;
+        below.hi[axis] = below.hi[axis].min(at);
+        let mut above = *self;
+        above.lo[axis] = above.lo[axis].max(at);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Removes `other` from `self`, returning the remainder as a set of disjoint boxes. The
+    /// result has at most `2 * N` boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+
+        // peel off the slabs on either side of the overlap one axis at a time, whatever is left
+        // after the last axis is the overlap itself
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..N {
+            let (below, middle) = rest.split(axis, overlap.lo[axis]);
+            pieces.extend(below);
+            let (middle, above) = middle.unwrap().split(axis, overlap.hi[axis]);
+            pieces.extend(above);
+            rest = middle.unwrap();
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::iproduct;
+
+    #[test]
+    fn test_volume() {
+        assert_eq!(24, HyperRect::new([0, 0, 0], [2, 3, 4]).volume());
+        assert_eq!(
+            27,
+            HyperRect::from_inclusive([10, 10, 10], [12, 12, 12]).volume()
+        );
+        assert_eq!(0, HyperRect::new([0, 5], [3, 5]).volume());
+        assert_eq!(0, HyperRect::new([0, 6], [3, 5]).volume());
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = HyperRect::new([0, 0], [4, 4]);
+        let b = HyperRect::new([2, -1], [6, 3]);
+        assert_eq!(Some(HyperRect::new([2, 0], [4, 3])), a.intersection(&b));
+        assert_eq!(None, a.intersection(&HyperRect::new([4, 0], [5, 4])));
+    }
+
+    #[test]
+    fn test_split() {
+        let a = HyperRect::new([0, 0], [4, 4]);
+        assert_eq!(
+            (
+                Some(HyperRect::new([0, 0], [4, 1])),
+                Some(HyperRect::new([0, 1], [4, 4]))
+            ),
+            a.split(1, 1)
+        );
+        assert_eq!((None, Some(a)), a.split(0, 0));
+        assert_eq!((Some(a), None), a.split(0, 10));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = HyperRect::new([0, 0, 0], [5, 5, 5]);
+        let b = HyperRect::new([1, 2, 3], [3, 7, 4]);
+        let pieces = a.subtract(&b);
+
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(
+            a.volume() - overlap.volume(),
+            pieces.iter().map(|p| p.volume()).sum()
+        );
+
+        // every point in a is covered exactly once by either the overlap or one of the pieces
+        for (x, y, z) in iproduct!(-1..6, -1..6, -1..6) {
+            let p = [x, y, z];
+            let covering = pieces.iter().filter(|piece| piece.contains(&p)).count();
+            if a.contains(&p) && !b.contains(&p) {
+                assert_eq!(1, covering, "{:?}", p);
+            } else {
+                assert_eq!(0, covering, "{:?}", p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subtract_disjoint() {
+        let a = HyperRect::new([0, 0], [2, 2]);
+        assert_eq!(vec![a], a.subtract(&HyperRect::new([5, 5], [6, 6])));
+        assert!(a.subtract(&HyperRect::new([-1, -1], [3, 3])).is_empty());
+    }
+}
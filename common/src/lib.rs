@@ -0,0 +1,20 @@
+// Building blocks shared between the yearly solution crates. Anything in here should be generic
+// enough to be used by more than one day.
+
+pub mod asm;
+pub mod dot;
+pub mod fraction;
+pub mod geometry;
+pub mod graph;
+pub mod hyperrect;
+pub mod linalg;
+pub mod maze;
+pub mod nested;
+pub mod netlist;
+pub mod numeral;
+pub mod numtheory;
+pub mod ocr;
+pub mod poly;
+pub mod symbolic;
+pub mod topsort;
+pub mod unionfind;
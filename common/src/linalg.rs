@@ -0,0 +1,379 @@
+// Exact linear algebra over rationals. Everything here is generic over `Scalar` so the same code
+// works with the (fast) i128 backed `Fraction` and with `num::BigRational` when the puzzle numbers
+// are too big for that.
+
+use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+
+use num::{BigInt, BigRational, One, ToPrimitive, Zero};
+
+use crate::fraction::Fraction;
+
+/// A number that can be used as a matrix element. Division must be exact.
+pub trait Scalar:
+    Clone
+    + PartialEq
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    fn from_i128(n: i128) -> Self;
+
+    /// Returns the value as an integer, or `None` if it has a fractional part or doesn't fit in
+    /// an i128.
+    fn to_i128(&self) -> Option<i128>;
+}
+
+impl Scalar for Fraction {
+    fn from_i128(n: i128) -> Self {
+        Fraction::from_integer(n)
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.to_integer()
+    }
+}
+
+impl Scalar for BigRational {
+    fn from_i128(n: i128) -> Self {
+        BigRational::from_integer(BigInt::from(n))
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        if self.is_integer() {
+            self.to_integer().to_i128()
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: Vec<Vec<T>>,
+    cols: usize,
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == cols),
+            "all matrix rows must be the same length"
+        );
+        Self { rows, cols }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows: vec![vec![T::zero(); cols]; rows],
+            cols,
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.rows[row]
+    }
+
+    /// Reduces the matrix to reduced row echelon form in place, returning the pivot column of
+    /// each non-zero row.
+    pub fn rref(&mut self) -> Vec<usize> {
+        self.rref_columns(self.cols)
+    }
+
+    /// Same as `rref`, but only the first `cols` columns are eligible to be pivots. This is what
+    /// you want for an augmented matrix where the last column is the right hand side.
+    fn rref_columns(&mut self, cols: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for col in 0..cols {
+            let pivot_row = pivots.len();
+            if pivot_row == self.rows.len() {
+                break;
+            }
+            let Some(found) = (pivot_row..self.rows.len()).find(|&r| !self.rows[r][col].is_zero())
+            else {
+                continue;
+            };
+            self.rows.swap(pivot_row, found);
+
+            let pivot_value = self.rows[pivot_row][col].clone();
+            for c in col..self.cols {
+                self.rows[pivot_row][c] = self.rows[pivot_row][c].clone() / pivot_value.clone();
+            }
+
+            for row in 0..self.rows.len() {
+                if row == pivot_row || self.rows[row][col].is_zero() {
+                    continue;
+                }
+                let factor = self.rows[row][col].clone();
+                for c in col..self.cols {
+                    let delta = factor.clone() * self.rows[pivot_row][c].clone();
+                    self.rows[row][c] = self.rows[row][c].clone() - delta;
+                }
+            }
+
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().rref().len()
+    }
+
+    /// A basis for the set of vectors `v` where `self * v == 0`.
+    pub fn nullspace(&self) -> Vec<Vec<T>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.rref();
+        (0..self.cols)
+            .filter(|col| !pivots.contains(col))
+            .map(|free| {
+                let mut v = vec![T::zero(); self.cols];
+                v[free] = T::one();
+                for (row, &pivot) in pivots.iter().enumerate() {
+                    v[pivot] = T::zero() - reduced[(row, free)].clone();
+                }
+                v
+            })
+            .collect()
+    }
+
+    pub fn mul_vec(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.cols, v.len());
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(v)
+                    .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+            })
+            .collect()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.rows[row][col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        &mut self.rows[row][col]
+    }
+}
+
+/// The linear system `A * x = b`, already reduced so that it can be solved for any choice of the
+/// free variables.
+#[derive(Debug, Clone)]
+pub struct LinearSystem<T> {
+    // the augmented matrix [A | b] in reduced row echelon form
+    reduced: Matrix<T>,
+    vars: usize,
+    pivots: Vec<usize>,
+    free: Vec<usize>,
+    consistent: bool,
+}
+
+impl<T: Scalar> LinearSystem<T> {
+    pub fn new(a: Matrix<T>, b: Vec<T>) -> Self {
+        assert_eq!(a.num_rows(), b.len());
+        let rows = a
+            .rows
+            .into_iter()
+            .zip(b)
+            .map(|(mut row, rhs)| {
+                row.push(rhs);
+                row
+            })
+            .collect();
+        Self::from_augmented(Matrix::new(rows))
+    }
+
+    /// Creates a system from the augmented matrix `[A | b]`.
+    pub fn from_augmented(mut augmented: Matrix<T>) -> Self {
+        let vars = augmented.num_cols() - 1;
+        let pivots = augmented.rref_columns(vars);
+        let free = (0..vars).filter(|col| !pivots.contains(col)).collect();
+        // any row without a pivot is all zeros on the left, so it must also be zero on the right
+        let consistent =
+            (pivots.len()..augmented.num_rows()).all(|r| augmented[(r, vars)].is_zero());
+        Self {
+            reduced: augmented,
+            vars,
+            pivots,
+            free,
+            consistent,
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// The variables that can take any value, every other variable is determined by these.
+    pub fn free_variables(&self) -> &[usize] {
+        &self.free
+    }
+
+    /// Solves for every variable given the values of the free variables, which must be in the
+    /// same order as `free_variables`.
+    pub fn solve_with(&self, free_values: &[T]) -> Vec<T> {
+        assert_eq!(self.free.len(), free_values.len());
+        let mut solution = vec![T::zero(); self.vars];
+        for (&col, value) in self.free.iter().zip(free_values) {
+            solution[col] = value.clone();
+        }
+        for (row, &pivot) in self.pivots.iter().enumerate() {
+            let mut value = self.reduced[(row, self.vars)].clone();
+            for (&col, free_value) in self.free.iter().zip(free_values) {
+                value = value - self.reduced[(row, col)].clone() * free_value.clone();
+            }
+            solution[pivot] = value;
+        }
+        solution
+    }
+
+    /// The solution of the system if there is exactly one.
+    pub fn solve(&self) -> Option<Vec<T>> {
+        if !self.consistent || !self.free.is_empty() {
+            return None;
+        }
+        Some(self.solve_with(&[]))
+    }
+
+    /// Calls `f` with every solution where all variables are non-negative integers. Each free
+    /// variable `i` is searched over `0..=upper_bounds[i]`, so `upper_bounds` has an entry for
+    /// every variable (the entries for the non-free variables are ignored).
+    pub fn for_each_nonnegative_integer_solution<F>(&self, upper_bounds: &[u64], mut f: F)
+    where
+        F: FnMut(&[u64]),
+    {
+        assert_eq!(self.vars, upper_bounds.len());
+        if !self.consistent {
+            return;
+        }
+        let mut free_values = vec![T::zero(); self.free.len()];
+        let mut solution = vec![0; self.vars];
+        self.search(0, upper_bounds, &mut free_values, &mut solution, &mut f);
+    }
+
+    fn search<F>(
+        &self,
+        free_index: usize,
+        upper_bounds: &[u64],
+        free_values: &mut [T],
+        solution: &mut [u64],
+        f: &mut F,
+    ) where
+        F: FnMut(&[u64]),
+    {
+        if free_index == self.free.len() {
+            for (value, out) in self.solve_with(free_values).iter().zip(solution.iter_mut()) {
+                match value.to_i128().and_then(|v| u64::try_from(v).ok()) {
+                    Some(v) => *out = v,
+                    None => return,
+                }
+            }
+            f(solution);
+            return;
+        }
+
+        for count in 0..=upper_bounds[self.free[free_index]] {
+            free_values[free_index] = T::from_i128(count as i128);
+            self.search(free_index + 1, upper_bounds, free_values, solution, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[i128]]) -> Matrix<Fraction> {
+        Matrix::new(
+            rows.iter()
+                .map(|r| r.iter().map(|&n| Fraction::from(n)).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_rref() {
+        let mut m = matrix(&[&[1, 2, -1, -4], &[2, 3, -1, -11], &[-2, 0, -3, 22]]);
+        let pivots = m.rref();
+        assert_eq!(vec![0, 1, 2], pivots);
+        assert_eq!(matrix(&[&[1, 0, 0, -8], &[0, 1, 0, 1], &[0, 0, 1, -2]]), m);
+    }
+
+    #[test]
+    fn test_rank_and_nullspace() {
+        let m = matrix(&[&[1, 2, 3], &[2, 4, 6], &[1, 0, 1]]);
+        assert_eq!(2, m.rank());
+        let nullspace = m.nullspace();
+        assert_eq!(1, nullspace.len());
+        assert!(m.mul_vec(&nullspace[0]).iter().all(Fraction::is_zero));
+        assert_eq!(3, Matrix::<Fraction>::identity(3).rank());
+        assert!(Matrix::<Fraction>::identity(3).nullspace().is_empty());
+    }
+
+    #[test]
+    fn test_solve() {
+        // 2x + y = 5, x - y = 1
+        let system =
+            LinearSystem::new(matrix(&[&[2, 1], &[1, -1]]), vec![5i64.into(), 1i64.into()]);
+        assert_eq!(Some(vec![2i64.into(), 1i64.into()]), system.solve());
+
+        let inconsistent =
+            LinearSystem::new(matrix(&[&[1, 1], &[2, 2]]), vec![1i64.into(), 3i64.into()]);
+        assert!(!inconsistent.is_consistent());
+        assert_eq!(None, inconsistent.solve());
+    }
+
+    #[test]
+    fn test_solve_big_rational() {
+        let big = |n: i128| BigRational::from_i128(n);
+        let huge = BigRational::from_integer(BigInt::from(10).pow(40));
+        // x + y = 10^40 + 1, x - y = 10^40 - 1
+        let a = Matrix::new(vec![vec![big(1), big(1)], vec![big(1), big(-1)]]);
+        let system = LinearSystem::new(a, vec![huge.clone() + big(1), huge.clone() - big(1)]);
+        assert_eq!(Some(vec![huge, big(1)]), system.solve());
+    }
+
+    #[test]
+    fn test_nonnegative_integer_solutions() {
+        // x + y + z = 3, y - z = 1 has solutions (2, 1, 0) and (0, 2, 1)
+        let system = LinearSystem::new(
+            matrix(&[&[1, 1, 1], &[0, 1, -1]]),
+            vec![3i64.into(), 1i64.into()],
+        );
+        assert_eq!(&[2], system.free_variables());
+        let mut solutions = Vec::new();
+        system.for_each_nonnegative_integer_solution(&[3, 3, 3], |s| solutions.push(s.to_vec()));
+        assert_eq!(vec![vec![2, 1, 0], vec![0, 2, 1]], solutions);
+    }
+}
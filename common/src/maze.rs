@@ -0,0 +1,226 @@
+// Helpers for grid mazes made of long single-width corridors. Walking those cell by cell is slow,
+// so the maze is contracted into a small weighted graph between the junctions first.
+
+use rayon::prelude::*;
+
+type Pos = (usize, usize);
+
+/// A maze contracted down to its junctions. Every edge is a corridor between two junctions
+/// weighted by its length in steps.
+#[derive(Debug, Clone, Default)]
+pub struct JunctionGraph {
+    /// The grid position of every junction, indexed by node id.
+    pub nodes: Vec<Pos>,
+    /// `edges[n]` holds `(to, steps)` for every corridor leaving node `n`.
+    pub edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl JunctionGraph {
+    /// Contracts the corridors of a grid maze. `moves` returns every cell that can be stepped to
+    /// from a cell, which is how walls (and one-way cells) are described. A cell is a junction if
+    /// it is one of `keep` (usually the start and end) or if three or more moves leave it.
+    ///
+    /// Corridors that dead end, or that can only be walked back the way they came, are dropped.
+    pub fn from_grid<F, I>(keep: &[Pos], moves: F) -> Self
+    where
+        F: Fn(Pos) -> I,
+        I: IntoIterator<Item = Pos>,
+    {
+        let mut graph = JunctionGraph::default();
+        for &pos in keep {
+            if graph.node(pos).is_none() {
+                graph.nodes.push(pos);
+                graph.edges.push(vec![]);
+            }
+        }
+
+        let is_junction =
+            |pos: Pos, nodes: &[Pos]| nodes.contains(&pos) || moves(pos).into_iter().count() >= 3;
+
+        // discover every junction reachable from the kept cells, following each corridor until
+        // it runs into the next junction
+        let mut idx = 0;
+        while idx < graph.nodes.len() {
+            let from = graph.nodes[idx];
+            for first in moves(from) {
+                let mut prev = from;
+                let mut cur = first;
+                let mut steps = 1;
+                let reached = loop {
+                    if is_junction(cur, &graph.nodes) {
+                        break Some(cur);
+                    }
+                    let Some(next) = moves(cur).into_iter().find(|&n| n != prev) else {
+                        break None;
+                    };
+                    (prev, cur) = (cur, next);
+                    steps += 1;
+                };
+
+                let Some(to) = reached else {
+                    continue;
+                };
+                let to = match graph.node(to) {
+                    Some(to) => to,
+                    None => {
+                        graph.nodes.push(to);
+                        graph.edges.push(vec![]);
+                        graph.nodes.len() - 1
+                    }
+                };
+                if to != idx {
+                    graph.edges[idx].push((to, steps));
+                }
+            }
+            idx += 1;
+        }
+        graph
+    }
+
+    /// The node id of the junction at `pos`.
+    pub fn node(&self, pos: Pos) -> Option<usize> {
+        self.nodes.iter().position(|&n| n == pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Length of the longest path from `start` to `end` that never visits a node twice, or
+    /// `None` if `end` can't be reached. This is NP-hard in general, it relies on the graph being
+    /// small (at most 64 nodes) so the visited set fits in a bitmask.
+    pub fn longest_simple_path(&self, start: usize, end: usize) -> Option<usize> {
+        let search = self.search(end);
+        search.dfs(start, 1 << start)
+    }
+
+    /// Same as `longest_simple_path`, but the first few levels of the search are fanned out over
+    /// the rayon thread pool.
+    pub fn longest_simple_path_par(&self, start: usize, end: usize) -> Option<usize> {
+        let search = self.search(end);
+
+        // expand the search breadth first until there is enough work to go around
+        let mut frontier = vec![(start, 1u64 << start, 0)];
+        let mut finished = None;
+        while !frontier.is_empty() && frontier.len() < 256 {
+            let mut next = Vec::new();
+            for (node, visited, dist) in frontier {
+                if node == end {
+                    finished = finished.max(Some(dist));
+                    continue;
+                }
+                for (to, steps) in search.moves(node) {
+                    if visited & (1 << to) == 0 {
+                        next.push((to, visited | (1 << to), dist + steps));
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        frontier
+            .into_par_iter()
+            .filter_map(|(node, visited, dist)| search.dfs(node, visited).map(|d| d + dist))
+            .max()
+            .max(finished)
+    }
+
+    fn search(&self, end: usize) -> LongestPath<'_> {
+        assert!(self.len() <= 64, "too many junctions for a u64 bitmask");
+        // if there is only one way in to the end, then once we get to that junction we have to
+        // go straight to the end or we can never get there
+        let into_end: Vec<_> = (0..self.len())
+            .filter(|&n| self.edges[n].iter().any(|&(to, _)| to == end))
+            .collect();
+        LongestPath {
+            graph: self,
+            end,
+            last_junction: (into_end.len() == 1).then(|| into_end[0]),
+        }
+    }
+}
+
+struct LongestPath<'a> {
+    graph: &'a JunctionGraph,
+    end: usize,
+    last_junction: Option<usize>,
+}
+
+impl LongestPath<'_> {
+    fn moves(&self, node: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let forced = Some(node) == self.last_junction;
+        self.graph.edges[node]
+            .iter()
+            .copied()
+            .filter(move |&(to, _)| !forced || to == self.end)
+    }
+
+    fn dfs(&self, node: usize, visited: u64) -> Option<usize> {
+        if node == self.end {
+            return Some(0);
+        }
+        let mut longest = None;
+        for (to, steps) in self.moves(node) {
+            if visited & (1 << to) != 0 {
+                continue;
+            }
+            if let Some(d) = self.dfs(to, visited | (1 << to)) {
+                longest = longest.max(Some(d + steps));
+            }
+        }
+        longest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+#.#####
+#.....#
+#.###.#
+#.....#
+###.#.#
+###...#
+#####.#";
+
+    fn graph(maze: &str) -> (JunctionGraph, usize, usize) {
+        let grid: Vec<Vec<u8>> = maze.lines().map(|l| l.bytes().collect()).collect();
+        let start = (0, 1);
+        let end = (grid.len() - 1, 5);
+        let graph = JunctionGraph::from_grid(&[start, end], |(r, c)| {
+            let grid = &grid;
+            [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .into_iter()
+                .filter_map(move |(dr, dc)| {
+                    let (r, c) = (r.checked_add_signed(dr)?, c.checked_add_signed(dc)?);
+                    (grid.get(r)?.get(c)? != &b'#').then_some((r, c))
+                })
+        });
+        let (s, e) = (graph.node(start).unwrap(), graph.node(end).unwrap());
+        (graph, s, e)
+    }
+
+    #[test]
+    fn test_contract() {
+        let (graph, _, _) = graph(MAZE);
+        // start, end and the junctions at (1, 1), (3, 3), (3, 5) and (5, 5)
+        assert_eq!(6, graph.len());
+        let total: usize = graph.edges.iter().flatten().map(|(_, steps)| steps).sum();
+        // every corridor is stored in both directions
+        assert_eq!(2 * 20, total);
+    }
+
+    #[test]
+    fn test_longest_simple_path() {
+        let (graph, start, end) = graph(MAZE);
+        assert_eq!(Some(14), graph.longest_simple_path(start, end));
+        assert_eq!(Some(14), graph.longest_simple_path_par(start, end));
+        assert_eq!(Some(0), graph.longest_simple_path(start, start));
+    }
+}
@@ -0,0 +1,426 @@
+// Arbitrarily nested lists of integers written like `[1,[2,3],[]]`, e.g. the distress signal
+// packets of 2022 day 13 and the snailfish numbers of 2021 day 18. Nodes are addressed by their
+// path, the index to take at each level down from the root.
+
+use std::{cmp::Ordering, fmt, mem, str::FromStr};
+
+use anyhow::{bail, ensure};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Nested {
+    Int(i64),
+    List(Vec<Nested>),
+}
+
+impl Nested {
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Nested::Int(n) => Some(*n),
+            Nested::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Nested]> {
+        match self {
+            Nested::Int(_) => None,
+            Nested::List(items) => Some(items),
+        }
+    }
+
+    /// The packet order from 2022 day 13: lists compare element by element and then by length,
+    /// and an integer compared with a list acts like a list of just that integer.
+    pub fn packet_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Nested::Int(l), Nested::Int(r)) => l.cmp(r),
+            (Nested::Int(_), Nested::List(r)) => cmp_lists(std::slice::from_ref(self), r),
+            (Nested::List(l), Nested::Int(_)) => cmp_lists(l, std::slice::from_ref(other)),
+            (Nested::List(l), Nested::List(r)) => cmp_lists(l, r),
+        }
+    }
+
+    /// Builds a value bottom up, `int` turning integers into values and `list` combining the
+    /// values of a list's items.
+    pub fn fold<T>(&self, int: &impl Fn(i64) -> T, list: &impl Fn(Vec<T>) -> T) -> T {
+        match self {
+            Nested::Int(n) => int(*n),
+            Nested::List(items) => list(items.iter().map(|item| item.fold(int, list)).collect()),
+        }
+    }
+
+    /// Every node with its depth, depth first, parents before their items.
+    pub fn walk(&self) -> impl Iterator<Item = (usize, &Nested)> {
+        let mut stack = vec![(0, self)];
+        std::iter::from_fn(move || {
+            let (depth, node) = stack.pop()?;
+            if let Nested::List(items) = node {
+                stack.extend(items.iter().rev().map(|item| (depth + 1, item)));
+            }
+            Some((depth, node))
+        })
+    }
+
+    /// The integers from left to right.
+    pub fn ints(&self) -> impl Iterator<Item = i64> + '_ {
+        self.walk().filter_map(|(_, node)| node.as_int())
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<&Nested> {
+        path.iter()
+            .try_fold(self, |node, &i| node.as_list()?.get(i))
+    }
+
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Nested> {
+        path.iter().try_fold(self, |node, &i| match node {
+            Nested::Int(_) => None,
+            Nested::List(items) => items.get_mut(i),
+        })
+    }
+
+    /// The path to the first node, in the order of `walk`, that matches `pred`.
+    pub fn find(&self, mut pred: impl FnMut(&[usize], &Nested) -> bool) -> Option<Vec<usize>> {
+        let mut path = Vec::new();
+        self.find_from(&mut path, &mut pred).then_some(path)
+    }
+
+    fn find_from(
+        &self,
+        path: &mut Vec<usize>,
+        pred: &mut impl FnMut(&[usize], &Nested) -> bool,
+    ) -> bool {
+        if pred(path, self) {
+            return true;
+        }
+        if let Nested::List(items) = self {
+            for (i, item) in items.iter().enumerate() {
+                path.push(i);
+                if item.find_from(path, pred) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+
+    /// Replaces the first node, in the order of `walk`, that `f` returns a replacement for.
+    /// Returns whether anything was replaced.
+    pub fn rewrite_first(
+        &mut self,
+        mut f: impl FnMut(&[usize], &Nested) -> Option<Nested>,
+    ) -> bool {
+        let mut replacement = None;
+        let Some(path) = self.find(|path, node| {
+            replacement = f(path, node);
+            replacement.is_some()
+        }) else {
+            return false;
+        };
+        *self.get_mut(&path).unwrap() = replacement.unwrap();
+        true
+    }
+
+    /// A cursor on the node at `path`, or `None` if there isn't one.
+    pub fn cursor(&mut self, path: Vec<usize>) -> Option<Cursor<'_>> {
+        self.get(&path)?;
+        Some(Cursor { root: self, path })
+    }
+
+    // the path to the first (or last) integer in the node
+    fn edge_int(&self, first: bool) -> Option<Vec<usize>> {
+        match self {
+            Nested::Int(_) => Some(vec![]),
+            Nested::List(items) => ordered(0..items.len(), first).find_map(|i| {
+                let mut path = items[i].edge_int(first)?;
+                path.insert(0, i);
+                Some(path)
+            }),
+        }
+    }
+}
+
+fn cmp_lists(l: &[Nested], r: &[Nested]) -> Ordering {
+    l.iter()
+        .zip(r)
+        .map(|(l, r)| l.packet_cmp(r))
+        .find(|&ord| ord != Ordering::Equal)
+        .unwrap_or_else(|| l.len().cmp(&r.len()))
+}
+
+fn ordered(range: std::ops::Range<usize>, forward: bool) -> impl Iterator<Item = usize> {
+    let mut indices: Vec<_> = range.collect();
+    if !forward {
+        indices.reverse();
+    }
+    indices.into_iter()
+}
+
+/// Points at one node of a value and moves around it, for changes that depend on the nodes
+/// around the one being changed.
+pub struct Cursor<'a> {
+    root: &'a mut Nested,
+    path: Vec<usize>,
+}
+
+impl Cursor<'_> {
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    pub fn focus(&self) -> &Nested {
+        self.root.get(&self.path).unwrap()
+    }
+
+    pub fn focus_mut(&mut self) -> &mut Nested {
+        self.root.get_mut(&self.path).unwrap()
+    }
+
+    /// Puts `node` in place of the focus, returning the old one.
+    pub fn replace(&mut self, node: Nested) -> Nested {
+        mem::replace(self.focus_mut(), node)
+    }
+
+    /// Moves to the parent. Returns false at the root.
+    pub fn up(&mut self) -> bool {
+        self.path.pop().is_some()
+    }
+
+    /// Moves to the `i`th item of the focus. Returns false if there isn't one.
+    pub fn down(&mut self, i: usize) -> bool {
+        let exists = self.focus().as_list().is_some_and(|items| i < items.len());
+        if exists {
+            self.path.push(i);
+        }
+        exists
+    }
+
+    /// Moves to the closest integer after the focus (and anything in it). Returns false, without
+    /// moving, if there isn't one.
+    pub fn next_int(&mut self) -> bool {
+        self.step_int(true)
+    }
+
+    /// Moves to the closest integer before the focus. Returns false, without moving, if there
+    /// isn't one.
+    pub fn prev_int(&mut self) -> bool {
+        self.step_int(false)
+    }
+
+    fn step_int(&mut self, forward: bool) -> bool {
+        let mut path = self.path.clone();
+        while let Some(i) = path.pop() {
+            let siblings = self.root.get(&path).and_then(Nested::as_list).unwrap();
+            let range = if forward { i + 1..siblings.len() } else { 0..i };
+            let found = ordered(range, forward).find_map(|j| {
+                let rest = siblings[j].edge_int(forward)?;
+                Some((j, rest))
+            });
+            if let Some((j, rest)) = found {
+                path.push(j);
+                path.extend(rest);
+                self.path = path;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl From<i64> for Nested {
+    fn from(n: i64) -> Self {
+        Nested::Int(n)
+    }
+}
+
+impl From<Vec<Nested>> for Nested {
+    fn from(items: Vec<Nested>) -> Self {
+        Nested::List(items)
+    }
+}
+
+impl fmt::Display for Nested {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Nested::Int(n) => write!(f, "{n}"),
+            Nested::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl FromStr for Nested {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pos = 0;
+        let value = parse(s.as_bytes(), &mut pos)?;
+        skip_spaces(s.as_bytes(), &mut pos);
+        ensure!(pos == s.len(), "unexpected '{}' at {pos}", &s[pos..]);
+        Ok(value)
+    }
+}
+
+fn skip_spaces(s: &[u8], pos: &mut usize) {
+    while s.get(*pos).is_some_and(u8::is_ascii_whitespace) {
+        *pos += 1;
+    }
+}
+
+fn parse(s: &[u8], pos: &mut usize) -> anyhow::Result<Nested> {
+    skip_spaces(s, pos);
+    match s.get(*pos) {
+        Some(b'[') => {
+            *pos += 1;
+            let mut items = Vec::new();
+            skip_spaces(s, pos);
+            if s.get(*pos) == Some(&b']') {
+                *pos += 1;
+                return Ok(Nested::List(items));
+            }
+            loop {
+                items.push(parse(s, pos)?);
+                skip_spaces(s, pos);
+                match s.get(*pos) {
+                    Some(b',') => *pos += 1,
+                    Some(b']') => {
+                        *pos += 1;
+                        return Ok(Nested::List(items));
+                    }
+                    _ => bail!("expected ',' or ']' at {pos}"),
+                }
+            }
+        }
+        Some(b'-' | b'0'..=b'9') => {
+            let start = *pos;
+            *pos += 1;
+            while s.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
+            }
+            let digits = std::str::from_utf8(&s[start..*pos])?;
+            match digits.parse() {
+                Ok(n) => Ok(Nested::Int(n)),
+                Err(e) => bail!("bad integer '{digits}' at {start}: {e}"),
+            }
+        }
+        _ => bail!("expected a list or an integer at {pos}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn n(s: &str) -> Nested {
+        s.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("[]", "[]")]
+    #[case("-12", "-12")]
+    #[case("[1,[2,[3,[4,[5,6,7]]]],8,9]", "[1,[2,[3,[4,[5,6,7]]]],8,9]")]
+    #[case(" [ 1, [ ], -2 ] ", "[1,[],-2]")]
+    fn test_parse(#[case] s: &str, #[case] expected: &str) {
+        assert_eq!(expected, n(s).to_string());
+    }
+
+    #[rstest]
+    #[case("", "expected a list or an integer at 0")]
+    #[case("[1,2", "expected ',' or ']' at 4")]
+    #[case("[1,,2]", "expected a list or an integer at 3")]
+    #[case("[1]]", "unexpected ']' at 3")]
+    #[case("[-]", "bad integer '-' at 1: invalid digit found in string")]
+    fn test_parse_errors(#[case] s: &str, #[case] expected: &str) {
+        assert_eq!(expected, s.parse::<Nested>().unwrap_err().to_string());
+    }
+
+    #[rstest]
+    #[case("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less)]
+    #[case("[[1],[2,3,4]]", "[[1],4]", Ordering::Less)]
+    #[case("[9]", "[[8,7,6]]", Ordering::Greater)]
+    #[case("[[4,4],4,4]", "[[4,4],4,4,4]", Ordering::Less)]
+    #[case("[7,7,7,7]", "[7,7,7]", Ordering::Greater)]
+    #[case("[]", "[3]", Ordering::Less)]
+    #[case("[[[]]]", "[[]]", Ordering::Greater)]
+    #[case(
+        "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+        "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+        Ordering::Greater
+    )]
+    #[case("[[2]]", "2", Ordering::Equal)]
+    fn test_packet_cmp(#[case] l: &str, #[case] r: &str, #[case] expected: Ordering) {
+        assert_eq!(expected, n(l).packet_cmp(&n(r)));
+        assert_eq!(expected.reverse(), n(r).packet_cmp(&n(l)));
+    }
+
+    #[test]
+    fn test_traversal() {
+        let value = n("[1,[2,[]],3]");
+        let depths: Vec<_> = value
+            .walk()
+            .map(|(depth, node)| (depth, node.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, "[1,[2,[]],3]".to_string()),
+                (1, "1".to_string()),
+                (1, "[2,[]]".to_string()),
+                (2, "2".to_string()),
+                (2, "[]".to_string()),
+                (1, "3".to_string()),
+            ],
+            depths
+        );
+        assert_eq!(vec![1, 2, 3], value.ints().collect::<Vec<_>>());
+        assert_eq!(Some(&Nested::Int(2)), value.get(&[1, 0]));
+        assert_eq!(None, value.get(&[0, 0]));
+        assert_eq!(
+            Some(vec![1, 1]),
+            value.find(|_, node| node == &Nested::List(vec![]))
+        );
+        let deepest = value.fold(&|_| 0, &|depths| 1 + depths.into_iter().max().unwrap_or(0));
+        assert_eq!(3, deepest);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut value = n("[[1,[]],[[2]],3]");
+        let mut cursor = value.cursor(vec![1]).unwrap();
+        assert!(cursor.prev_int());
+        assert_eq!(&[0, 0], cursor.path());
+        assert!(!cursor.prev_int());
+        assert!(cursor.next_int());
+        assert_eq!(&Nested::Int(2), cursor.focus());
+        cursor.replace(Nested::Int(20));
+        assert!(cursor.up() && cursor.up());
+        assert!(cursor.next_int());
+        assert!(!cursor.next_int());
+        assert_eq!(&[2], cursor.path());
+        assert!(!cursor.down(0));
+        assert_eq!("[[1,[]],[[20]],3]", value.to_string());
+        assert!(value.cursor(vec![3]).is_none());
+    }
+
+    #[test]
+    fn test_rewrite_first() {
+        let mut value = n("[1,[5,7],6]");
+        let odd_to_pair = |_: &[usize], node: &Nested| match node {
+            Nested::Int(n) if n % 2 == 1 && *n > 1 => {
+                Some(vec![Nested::Int(1), Nested::Int(n - 1)].into())
+            }
+            _ => None,
+        };
+        assert!(value.rewrite_first(odd_to_pair));
+        assert_eq!("[1,[[1,4],7],6]", value.to_string());
+        assert!(value.rewrite_first(odd_to_pair));
+        assert!(!value.rewrite_first(odd_to_pair));
+        assert_eq!("[1,[[1,4],[1,6]],6]", value.to_string());
+    }
+}
@@ -0,0 +1,365 @@
+// A circuit of named wires, each driven by a single gate that reads other wires. Puzzles like 2015
+// day07 (16 bit logic), 2024 day24 (boolean logic) and 2022 day21 (monkey arithmetic) are all this
+// shape and only differ in what their gates compute.
+
+use anyhow::Context;
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::dot::{Dot, ToDot};
+
+/// Something that drives a wire in a `Netlist`.
+pub trait Gate {
+    type Value: Clone;
+
+    /// The wires this gate reads.
+    fn inputs(&self) -> Vec<&str>;
+
+    /// Computes the gate's output. `input` returns the value of any wire from `inputs`.
+    fn eval(&self, input: &dyn Fn(&str) -> Self::Value) -> Self::Value;
+
+    /// Extra graphviz attributes for the wire this gate drives, e.g. `("color", "red")`.
+    fn dot_attrs(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+}
+
+pub struct Netlist<G: Gate> {
+    gates: FxHashMap<String, G>,
+    // wires that read each wire, used to work out what needs recomputing after a change
+    fanout: FxHashMap<String, Vec<String>>,
+    overrides: FxHashMap<String, G::Value>,
+    values: FxHashMap<String, G::Value>,
+}
+
+impl<G: Gate> Default for Netlist<G> {
+    fn default() -> Self {
+        Self {
+            gates: Default::default(),
+            fanout: Default::default(),
+            overrides: Default::default(),
+            values: Default::default(),
+        }
+    }
+}
+
+impl<G: Gate> FromIterator<(String, G)> for Netlist<G> {
+    fn from_iter<T: IntoIterator<Item = (String, G)>>(iter: T) -> Self {
+        let mut netlist = Netlist::new();
+        for (wire, gate) in iter {
+            netlist.add(wire, gate);
+        }
+        netlist
+    }
+}
+
+impl<G: Gate> Netlist<G> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Drives `wire` with `gate`, replacing whatever drove it before.
+    pub fn add(&mut self, wire: impl Into<String>, gate: G) {
+        let wire = wire.into();
+        if let Some(old) = self.gates.get(&wire) {
+            for input in old.inputs() {
+                if let Some(readers) = self.fanout.get_mut(input) {
+                    readers.retain(|r| *r != wire);
+                }
+            }
+        }
+        for input in gate.inputs() {
+            self.fanout
+                .entry(input.to_string())
+                .or_default()
+                .push(wire.clone());
+        }
+        self.invalidate(&wire);
+        self.gates.insert(wire, gate);
+    }
+
+    pub fn gate(&self, wire: &str) -> Option<&G> {
+        self.gates.get(wire)
+    }
+
+    /// Every wire that is driven by a gate.
+    pub fn wires(&self) -> impl Iterator<Item = &str> {
+        self.gates.keys().map(String::as_str)
+    }
+
+    /// Forces `wire` to `value` no matter what its gate computes. Only the wires downstream of
+    /// `wire` are recomputed the next time they are read.
+    pub fn set(&mut self, wire: &str, value: G::Value) {
+        self.invalidate(wire);
+        self.overrides.insert(wire.to_string(), value);
+    }
+
+    /// Removes an override added by `set`, letting the wire's gate drive it again.
+    pub fn unset(&mut self, wire: &str) {
+        if self.overrides.remove(wire).is_some() {
+            self.invalidate(wire);
+        }
+    }
+
+    /// The value on `wire`, evaluating (and remembering) everything it depends on. Fails if the
+    /// wire depends on itself or on a wire that nothing drives.
+    pub fn get(&mut self, wire: &str) -> anyhow::Result<G::Value> {
+        if let Some(v) = self.known(wire) {
+            return Ok(v.clone());
+        }
+
+        // depth first, a wire is evaluated once everything it reads has been. `path` holds the
+        // wires we are part way through so we can spot loops.
+        let mut stack = vec![(wire.to_string(), false)];
+        let mut path = FxHashSet::default();
+        while let Some((w, inputs_ready)) = stack.pop() {
+            if self.known(&w).is_some() {
+                continue;
+            }
+            let gate = self
+                .gates
+                .get(&w)
+                .with_context(|| format!("wire '{w}' is not driven by anything"))?;
+
+            if inputs_ready {
+                let values = &self.values;
+                let overrides = &self.overrides;
+                let value = gate.eval(&|input| {
+                    overrides
+                        .get(input)
+                        .or_else(|| values.get(input))
+                        .cloned()
+                        .unwrap()
+                });
+                path.remove(&w);
+                self.values.insert(w, value);
+                continue;
+            }
+
+            anyhow::ensure!(path.insert(w.clone()), "wire '{w}' depends on itself");
+            let inputs: Vec<String> = gate
+                .inputs()
+                .into_iter()
+                .filter(|i| self.known(i).is_none())
+                .map(str::to_string)
+                .collect();
+            stack.push((w, true));
+            for input in inputs {
+                anyhow::ensure!(!path.contains(&input), "wire '{input}' depends on itself");
+                stack.push((input, false));
+            }
+        }
+
+        Ok(self.known(wire).unwrap().clone())
+    }
+
+    /// Evaluates every wire.
+    pub fn evaluate(&mut self) -> anyhow::Result<FxHashMap<String, G::Value>> {
+        let wires: Vec<String> = self.gates.keys().cloned().collect();
+        wires
+            .into_iter()
+            .map(|w| self.get(&w).map(|v| (w, v)))
+            .collect()
+    }
+
+    fn known(&self, wire: &str) -> Option<&G::Value> {
+        self.overrides.get(wire).or_else(|| self.values.get(wire))
+    }
+
+    // forgets the value of `wire` and everything computed from it. A wire without a value can't
+    // have been read by anything yet, and overridden wires don't depend on their inputs, so the
+    // search can stop at either.
+    fn invalidate(&mut self, wire: &str) {
+        self.values.remove(wire);
+        let mut stack: Vec<String> = self.fanout.get(wire).cloned().unwrap_or_default();
+        while let Some(w) = stack.pop() {
+            if self.values.remove(&w).is_some()
+                && let Some(readers) = self.fanout.get(&w)
+            {
+                stack.extend(readers.iter().cloned());
+            }
+        }
+    }
+}
+
+impl<G: Gate> ToDot for Netlist<G> {
+    /// Draws the circuit with an edge from every wire to the wires that read it.
+    fn to_dot(&self) -> Dot {
+        let mut wires: Vec<&String> = self.gates.keys().collect();
+        wires.sort();
+
+        let mut dot = Dot::digraph();
+        for wire in wires {
+            let gate = &self.gates[wire];
+            let attrs = gate.dot_attrs();
+            if !attrs.is_empty() {
+                dot.node(wire.as_str(), attrs);
+            }
+            for input in gate.inputs() {
+                dot.edge(input, wire.as_str(), vec![]);
+            }
+        }
+        dot
+    }
+}
+
+/// One side of a `Logic` gate, either a fixed value or another wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Value(u64),
+    Wire(String),
+}
+
+impl Operand {
+    fn value(&self, input: &dyn Fn(&str) -> u64) -> u64 {
+        match self {
+            Operand::Value(v) => *v,
+            Operand::Wire(w) => input(w),
+        }
+    }
+}
+
+/// Bitwise logic on `WIDTH` bit wide wires, e.g. `Logic<1>` for booleans and `Logic<16>` for the
+/// 2015 day07 circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Logic<const WIDTH: u32> {
+    Buf(Operand),
+    Not(Operand),
+    And(Operand, Operand),
+    Or(Operand, Operand),
+    Xor(Operand, Operand),
+    LShift(Operand, u32),
+    RShift(Operand, u32),
+}
+
+impl<const WIDTH: u32> Logic<WIDTH> {
+    pub const MASK: u64 = if WIDTH >= 64 {
+        u64::MAX
+    } else {
+        (1 << WIDTH) - 1
+    };
+
+    fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Logic::Buf(a) | Logic::Not(a) | Logic::LShift(a, _) | Logic::RShift(a, _) => vec![a],
+            Logic::And(a, b) | Logic::Or(a, b) | Logic::Xor(a, b) => vec![a, b],
+        }
+    }
+}
+
+impl<const WIDTH: u32> Gate for Logic<WIDTH> {
+    type Value = u64;
+
+    fn inputs(&self) -> Vec<&str> {
+        self.operands()
+            .into_iter()
+            .filter_map(|o| match o {
+                Operand::Wire(w) => Some(w.as_str()),
+                Operand::Value(_) => None,
+            })
+            .collect()
+    }
+
+    fn eval(&self, input: &dyn Fn(&str) -> u64) -> u64 {
+        let v = |o: &Operand| o.value(input);
+        let res = match self {
+            Logic::Buf(a) => v(a),
+            Logic::Not(a) => !v(a),
+            Logic::And(a, b) => v(a) & v(b),
+            Logic::Or(a, b) => v(a) | v(b),
+            Logic::Xor(a, b) => v(a) ^ v(b),
+            Logic::LShift(a, n) => v(a).checked_shl(*n).unwrap_or(0),
+            Logic::RShift(a, n) => v(a).checked_shr(*n).unwrap_or(0),
+        };
+        res & Self::MASK
+    }
+
+    fn dot_attrs(&self) -> Vec<(&'static str, String)> {
+        let (label, color) = match self {
+            Logic::Buf(_) => ("BUF", "black"),
+            Logic::Not(_) => ("NOT", "orange"),
+            Logic::And(..) => ("AND", "green"),
+            Logic::Or(..) => ("OR", "blue"),
+            Logic::Xor(..) => ("XOR", "red"),
+            Logic::LShift(..) => ("LSHIFT", "purple"),
+            Logic::RShift(..) => ("RSHIFT", "purple"),
+        };
+        vec![("xlabel", label.to_string()), ("color", color.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(name: &str) -> Operand {
+        Operand::Wire(name.to_string())
+    }
+
+    // 2015 day07 sample
+    fn sample() -> Netlist<Logic<16>> {
+        Netlist::from_iter([
+            ("x".to_string(), Logic::Buf(Operand::Value(123))),
+            ("y".to_string(), Logic::Buf(Operand::Value(456))),
+            ("d".to_string(), Logic::And(wire("x"), wire("y"))),
+            ("e".to_string(), Logic::Or(wire("x"), wire("y"))),
+            ("f".to_string(), Logic::LShift(wire("x"), 2)),
+            ("g".to_string(), Logic::RShift(wire("y"), 2)),
+            ("h".to_string(), Logic::Not(wire("x"))),
+            ("i".to_string(), Logic::Not(wire("y"))),
+        ])
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let values = sample().evaluate().unwrap();
+        let expected = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, value) in expected {
+            assert_eq!(value, values[wire], "{wire}");
+        }
+    }
+
+    #[test]
+    fn test_override_propagates() {
+        let mut netlist = sample();
+        assert_eq!(72, netlist.get("d").unwrap());
+        assert_eq!(492, netlist.get("f").unwrap());
+        netlist.set("x", 0xffff);
+        assert_eq!(456, netlist.get("d").unwrap());
+        assert_eq!(0xfffc, netlist.get("f").unwrap());
+        assert_eq!(114, netlist.get("g").unwrap());
+        netlist.unset("x");
+        assert_eq!(72, netlist.get("d").unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut netlist: Netlist<Logic<1>> = Netlist::from_iter([
+            ("a".to_string(), Logic::And(wire("b"), wire("c"))),
+            ("b".to_string(), Logic::Not(wire("a"))),
+            ("c".to_string(), Logic::Buf(Operand::Value(1))),
+            ("d".to_string(), Logic::Buf(wire("missing"))),
+        ]);
+        assert!(netlist.get("a").is_err());
+        assert!(netlist.get("d").is_err());
+        assert_eq!(1, netlist.get("c").unwrap());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let netlist: Netlist<Logic<1>> =
+            Netlist::from_iter([("z".to_string(), Logic::Xor(wire("x"), wire("y")))]);
+        assert_eq!(
+            "digraph {\n  \"z\" [xlabel=\"XOR\", color=\"red\"];\n  \"x\" -> \"z\";\n  \"y\" -> \"z\";\n}\n",
+            netlist.to_dot().to_string()
+        );
+    }
+}
@@ -0,0 +1,268 @@
+// Positional numeral systems where the digits are any run of consecutive values containing zero,
+// e.g. plain binary (0 to 1), balanced ternary (-1 to 1) or 2022 day 25's SNAFU (-2 to 2). A
+// system is just its digit characters from lowest to highest and the value of the lowest one.
+
+use anyhow::{Context, bail, ensure};
+use num::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Integer, ToPrimitive};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numeral {
+    digits: Vec<char>,
+    lowest: i64,
+}
+
+impl Numeral {
+    /// `digits` from lowest to highest, the first one being worth `lowest`.
+    pub fn new(digits: &str, lowest: i64) -> anyhow::Result<Self> {
+        let digits: Vec<char> = digits.chars().collect();
+        ensure!(digits.len() >= 2, "need at least two digits");
+        for (i, d) in digits.iter().enumerate() {
+            ensure!(!digits[..i].contains(d), "'{d}' is in there twice");
+        }
+        let highest = lowest + digits.len() as i64 - 1;
+        ensure!(
+            lowest <= 0 && 0 <= highest,
+            "digits from {lowest} to {highest} don't include zero"
+        );
+        Ok(Self { digits, lowest })
+    }
+
+    /// The 2022 day 25 digits, `=` and `-` being -2 and -1.
+    pub fn snafu() -> Self {
+        Self::new("=-012", -2).unwrap()
+    }
+
+    pub fn balanced_ternary() -> Self {
+        Self::new("-0+", -1).unwrap()
+    }
+
+    pub fn base(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn digit_value(&self, d: char) -> Option<i64> {
+        let i = self.digits.iter().position(|&c| c == d)?;
+        Some(self.lowest + i as i64)
+    }
+
+    fn digit(&self, value: i64) -> char {
+        self.digits[(value - self.lowest) as usize]
+    }
+
+    // the digit that's congruent to `n`, and what's carried to the next place
+    fn split(&self, n: i64) -> (i64, i64) {
+        let base = self.base() as i64;
+        let digit = self.lowest + (n - self.lowest).rem_euclid(base);
+        (digit, (n - digit) / base)
+    }
+
+    /// Reads a number, failing on anything that isn't a digit or if it doesn't fit in `T`.
+    pub fn decode<T>(&self, s: &str) -> anyhow::Result<T>
+    where
+        T: Integer + FromPrimitive + CheckedAdd + CheckedSub + CheckedMul,
+    {
+        ensure!(!s.is_empty(), "no digits");
+        let base = T::from_usize(self.base()).context("base doesn't fit")?;
+        s.chars().enumerate().try_fold(T::zero(), |n, (i, d)| {
+            let value = self
+                .digit_value(d)
+                .with_context(|| format!("'{d}' at {i} isn't a digit"))?;
+            // negative digits are subtracted so that unsigned types work too
+            let abs = T::from_u64(value.unsigned_abs()).context("digit doesn't fit")?;
+            n.checked_mul(&base)
+                .and_then(|n| {
+                    if value < 0 {
+                        n.checked_sub(&abs)
+                    } else {
+                        n.checked_add(&abs)
+                    }
+                })
+                .with_context(|| format!("'{s}' doesn't fit"))
+        })
+    }
+
+    /// Writes a number, without leading zeros. Fails if it can't be written with these digits,
+    /// i.e. it's negative and there are no negative digits or the other way round.
+    pub fn encode<T>(&self, n: &T) -> anyhow::Result<String>
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive + CheckedAdd,
+    {
+        let highest = self.lowest + self.base() as i64 - 1;
+        if (*n < T::zero() && self.lowest == 0) || (*n > T::zero() && highest == 0) {
+            bail!(
+                "can't be written with digits from {} to {highest}",
+                self.lowest
+            );
+        }
+        let base = T::from_usize(self.base()).context("base doesn't fit")?;
+        let mut rest = n.clone();
+        let mut digits = Vec::new();
+        loop {
+            let r = rest
+                .mod_floor(&base)
+                .to_i64()
+                .context("remainder doesn't fit")?;
+            let (digit, _) = self.split(r);
+            digits.push(self.digit(digit));
+            // a negative digit takes the rest up to the next multiple of the base, which is
+            // one more in the next place
+            rest = rest.div_floor(&base);
+            if digit < 0 {
+                rest = rest.checked_add(&T::one()).context("overflow")?;
+            }
+            if rest.is_zero() {
+                break;
+            }
+        }
+        Ok(digits.iter().rev().collect())
+    }
+
+    /// Adds two numbers digit by digit without converting them.
+    pub fn add(&self, a: &str, b: &str) -> anyhow::Result<String> {
+        // digit values, lowest place first
+        let values = |s: &str| -> anyhow::Result<Vec<i64>> {
+            ensure!(!s.is_empty(), "no digits");
+            let mut values = s
+                .chars()
+                .enumerate()
+                .map(|(i, d)| {
+                    self.digit_value(d)
+                        .with_context(|| format!("'{d}' at {i} isn't a digit"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            values.reverse();
+            Ok(values)
+        };
+        let (a, b) = (values(a)?, values(b)?);
+        let mut digits = Vec::new();
+        let mut carry = 0;
+        for i in 0.. {
+            if i >= a.len() && i >= b.len() && carry == 0 {
+                break;
+            }
+            let sum = a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0) + carry;
+            let (digit, next) = self.split(sum);
+            digits.push(digit);
+            carry = next;
+        }
+        while digits.len() > 1 && digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Ok(digits.iter().rev().map(|&d| self.digit(d)).collect())
+    }
+
+    /// Adds up numbers digit by digit, see `add`.
+    pub fn sum<'a>(&self, numbers: impl IntoIterator<Item = &'a str>) -> anyhow::Result<String> {
+        numbers
+            .into_iter()
+            .try_fold(self.digit(0).to_string(), |acc, n| self.add(&acc, n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Numeral::snafu(), 1747, "1=-0-2")]
+    #[case(Numeral::snafu(), 0, "0")]
+    #[case(Numeral::snafu(), -3, "-2")]
+    #[case(Numeral::balanced_ternary(), 8, "+0-")]
+    #[case(Numeral::balanced_ternary(), -5, "-++")]
+    #[case(Numeral::new("01", 0).unwrap(), 6, "110")]
+    #[case(Numeral::new("-0", -1).unwrap(), -6, "--0")]
+    fn test_encode(#[case] numeral: Numeral, #[case] n: i64, #[case] expected: &str) {
+        assert_eq!(expected, numeral.encode(&n).unwrap());
+        assert_eq!(n, numeral.decode::<i64>(expected).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let numerals = [
+            Numeral::snafu(),
+            Numeral::balanced_ternary(),
+            Numeral::new("0123456789", 0).unwrap(),
+            Numeral::new("ab", 0).unwrap(),
+            Numeral::new("xyz", -2).unwrap(),
+            Numeral::new("abcdefg", -1).unwrap(),
+        ];
+        for numeral in &numerals {
+            // only the numbers the digits can write
+            let highest = numeral.lowest + numeral.base() as i64 - 1;
+            let range = |n: i64| match (numeral.lowest, highest) {
+                (0, _) => n.abs(),
+                (_, 0) => -n.abs(),
+                _ => n,
+            };
+            for n in (-500..=500).map(range) {
+                let s = numeral.encode(&n).unwrap();
+                assert!(s.len() == 1 || !s.starts_with(numeral.digit(0)), "{s}");
+                assert_eq!(n, numeral.decode::<i64>(&s).unwrap(), "{s}");
+            }
+            // unsigned types, even where the digits are negative
+            if highest > 0 {
+                for n in 0..=u8::MAX {
+                    let s = numeral.encode(&n).unwrap();
+                    assert_eq!(numeral.encode(&i64::from(n)).unwrap(), s);
+                    assert_eq!(n, numeral.decode::<u8>(&s).unwrap(), "{s}");
+                }
+            }
+            for a in (-40..=40).map(range) {
+                for b in (-40..=40).map(range) {
+                    let sum = numeral
+                        .add(&numeral.encode(&a).unwrap(), &numeral.encode(&b).unwrap())
+                        .unwrap();
+                    assert_eq!(numeral.encode(&(a + b)).unwrap(), sum, "{a} + {b}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_big() {
+        let snafu = Numeral::snafu();
+        let big: BigInt = num::pow(BigInt::from(5), 60) * 2 - 1;
+        let s = snafu.encode(&big).unwrap();
+        assert_eq!(format!("2{}", "0".repeat(59) + "-"), s);
+        assert_eq!(big, snafu.decode::<BigInt>(&s).unwrap());
+        assert!(snafu.decode::<i64>(&s).is_err());
+        assert_eq!(7, snafu.decode::<u8>("12").unwrap());
+        assert_eq!(3, snafu.decode::<u8>("1=").unwrap());
+        assert_eq!("1=", snafu.encode(&3u8).unwrap());
+        assert!(snafu.decode::<u8>("-2").is_err());
+        assert!(snafu.decode::<u8>("2===").is_ok());
+        assert!(snafu.decode::<u8>("2222").is_err());
+        assert_eq!(
+            snafu.encode(&(big.clone() + &big)).unwrap(),
+            snafu.add(&s, &s).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("0", 0)]
+    #[case("01", 1)]
+    #[case("01", -2)]
+    #[case("0011", 0)]
+    #[case("", 0)]
+    fn test_new_errors(#[case] digits: &str, #[case] lowest: i64) {
+        assert!(Numeral::new(digits, lowest).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let snafu = Numeral::snafu();
+        assert_eq!(
+            "'3' at 1 isn't a digit",
+            snafu.decode::<i64>("13").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "'3' at 1 isn't a digit",
+            snafu.add("13", "1").unwrap_err().to_string()
+        );
+        assert!(snafu.decode::<i64>("").is_err());
+        assert!(Numeral::new("01", 0).unwrap().encode(&-1).is_err());
+        assert_eq!("1=-", snafu.sum(["1", "2", "-", "1=", "2-"]).unwrap());
+    }
+}
@@ -0,0 +1,219 @@
+use num::{PrimInt, integer::Roots};
+
+/// Extended Euclidean algorithm. Returns `(g, x, y)` where `g = gcd(a, b)` (never negative) and
+/// `a * x + b * y == g`.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime. The result is in `0..m`.
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp mod m` by repeated squaring.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut res = 1u128;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    res as u64
+}
+
+/// Chinese Remainder Theorem. Finds `x` such that `x ≡ residue (mod modulus)` for every
+/// `(residue, modulus)` pair. The moduli do not need to be coprime.
+///
+/// Returns `(x, lcm)` where `x` is the smallest non-negative solution and every solution is
+/// `x + k * lcm`, or `None` if the congruences contradict each other.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut lcm = 1;
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive");
+        let residue = residue.rem_euclid(modulus);
+        let (g, _, _) = ext_gcd(lcm, modulus);
+        let diff = residue - x;
+        if diff % g != 0 {
+            return None;
+        }
+        // solve x + lcm * k ≡ residue (mod modulus) for k
+        let step = modulus / g;
+        let k = (diff / g).rem_euclid(step) * mod_inv(lcm / g, step)? % step;
+        x += lcm * k;
+        lcm *= step;
+        x = x.rem_euclid(lcm);
+    }
+    Some((x, lcm))
+}
+
+/// Least common multiple of every number, or 1 if there are none.
+pub fn lcm_all<T: PrimInt + num::Integer, I: IntoIterator<Item = T>>(nums: I) -> T {
+    nums.into_iter().fold(T::one(), |acc, n| acc.lcm(&n))
+}
+
+/// Calls `f` once with every divisor of `n` (including 1 and `n`), in no particular order.
+pub fn for_each_divisor<T: PrimInt + Roots, F: FnMut(T)>(n: T, mut f: F) {
+    let root = n.sqrt();
+    let mut d = T::one();
+    while d <= root {
+        if n % d == T::zero() {
+            f(d);
+            let comp = n / d;
+            if comp != d {
+                f(comp);
+            }
+        }
+        d = d + T::one();
+    }
+}
+
+/// Every divisor of `n` in ascending order.
+pub fn divisors<T: PrimInt + Roots>(n: T) -> Vec<T> {
+    let mut res = Vec::new();
+    for_each_divisor(n, |d| res.push(d));
+    res.sort();
+    res
+}
+
+/// Something that happens periodically, e.g. the button presses at which a module sends a pulse.
+/// It first happens at `offset` and then again every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub offset: u64,
+    pub period: u64,
+}
+
+impl Cycle {
+    /// Creates a cycle from the steps it was seen at the first and second time.
+    pub fn from_hits(first: u64, second: u64) -> Self {
+        assert!(first < second, "hits must be in order");
+        Self {
+            offset: first,
+            period: second - first,
+        }
+    }
+
+    /// Whether the cycle happens at every multiple of its period, i.e. it would have happened at
+    /// step 0 too. This is what makes it valid to combine cycles with a plain LCM.
+    pub fn starts_at_zero(&self) -> bool {
+        self.offset.is_multiple_of(self.period)
+    }
+}
+
+/// The first step at which every cycle happens, assuming they all start at offset zero (see
+/// `Cycle::starts_at_zero`). Returns `None` if that assumption doesn't hold, in which case
+/// `align_cycles` is needed instead.
+pub fn lcm_of_cycles(cycles: &[Cycle]) -> Option<u64> {
+    if !cycles.iter().all(Cycle::starts_at_zero) {
+        return None;
+    }
+    Some(lcm_all(cycles.iter().map(|c| c.period)))
+}
+
+/// The first step at which every cycle happens, using the CRT so the cycles can start anywhere.
+/// Returns `None` if the cycles never line up.
+pub fn align_cycles(cycles: &[Cycle]) -> Option<u64> {
+    let congruences: Vec<_> = cycles
+        .iter()
+        .map(|c| (c.offset as i128, c.period as i128))
+        .collect();
+    let (x, lcm) = crt(&congruences)?;
+    // every cycle has to have started, so skip ahead past the latest offset
+    let start = cycles.iter().map(|c| c.offset as i128).max().unwrap_or(0);
+    let x = if x < start {
+        x + (start - x + lcm - 1) / lcm * lcm
+    } else {
+        x
+    };
+    Some(x as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(240, 46, 2)]
+    #[case(-12, 18, 6)]
+    #[case(7, 0, 7)]
+    #[case(0, 0, 0)]
+    fn test_ext_gcd(#[case] a: i128, #[case] b: i128, #[case] expected: i128) {
+        let (g, x, y) = ext_gcd(a, b);
+        assert_eq!(expected, g);
+        assert_eq!(g, a * x + b * y);
+    }
+
+    #[test]
+    fn test_mod_inv() {
+        assert_eq!(Some(4), mod_inv(3, 11));
+        assert_eq!(Some(7), mod_inv(-3, 11));
+        assert_eq!(None, mod_inv(6, 9));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(445, mod_pow(4, 13, 497));
+        assert_eq!(0, mod_pow(4, 13, 1));
+        assert_eq!(1, mod_pow(u64::MAX, 0, 7));
+        assert_eq!(
+            (u64::MAX as u128 % 1_000_000_007).pow(2) % 1_000_000_007,
+            mod_pow(u64::MAX, 2, 1_000_000_007) as u128
+        );
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        // non-coprime moduli
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1, 6), (2, 4)]));
+        // 2020 day13 sample, the bus with id m leaves at t + offset so t ≡ -offset (mod m)
+        assert_eq!(
+            Some((1068781, 3162341)),
+            crt(&[(0, 7), (-1, 13), (-4, 59), (-6, 31), (-7, 19)])
+        );
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(vec![1], divisors(1u32));
+        assert_eq!(vec![1, 2, 3, 4, 6, 12], divisors(12usize));
+        assert_eq!(vec![1, 7, 49], divisors(49u64));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let zero = [Cycle::from_hits(4, 8), Cycle::from_hits(6, 12)];
+        assert_eq!(Some(12), lcm_of_cycles(&zero));
+        assert_eq!(Some(12), align_cycles(&zero));
+
+        let offset = [Cycle::from_hits(3, 7), Cycle::from_hits(5, 11)];
+        assert!(!offset[0].starts_at_zero());
+        assert_eq!(None, lcm_of_cycles(&offset));
+        assert_eq!(Some(11), align_cycles(&offset));
+    }
+}
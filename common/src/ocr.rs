@@ -0,0 +1,144 @@
+// Reads the block capitals that some puzzles draw their answer in, so they can be printed (and
+// tested) as text. Knows the 4x6 letters most years use and the 6x10 ones from 2018 day 10.
+
+const SMALL: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters drawn with `#` in `art`, anything else is off. `None` if there's a letter it
+/// doesn't know.
+pub fn read(art: &str) -> Option<String> {
+    let grid: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+    read_grid(&grid)
+}
+
+/// Same as `read`, for a grid of pixels. Rows don't have to be the same length.
+pub fn read_grid(grid: &[Vec<bool>]) -> Option<String> {
+    // only the rows with something on them, which have to be one of the letter heights
+    let first = grid.iter().position(|row| row.contains(&true))?;
+    let last = grid.iter().rposition(|row| row.contains(&true))?;
+    let rows = &grid[first..=last];
+    let width = rows.iter().map(Vec::len).max()?;
+    let on = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    let column = |x: usize| (0..rows.len()).map(|y| on(x, y)).collect::<Vec<_>>();
+
+    let font: Vec<(char, Vec<Vec<bool>>)> = match rows.len() {
+        6 => SMALL.iter().map(|(c, g)| (*c, columns(g))).collect(),
+        10 => LARGE.iter().map(|(c, g)| (*c, columns(g))).collect(),
+        _ => return None,
+    };
+
+    // letters are separated by at least one empty column, and don't have any empty columns in them
+    let mut letters = String::new();
+    let mut glyph = Vec::new();
+    for x in 0..=width {
+        let col = if x < width { column(x) } else { vec![] };
+        if col.contains(&true) {
+            glyph.push(col);
+        } else if !glyph.is_empty() {
+            let (letter, _) = font.iter().find(|(_, g)| *g == glyph)?;
+            letters.push(*letter);
+            glyph.clear();
+        }
+    }
+    Some(letters)
+}
+
+/// The letters in `art` if they can all be read, otherwise the art itself on a line of its own so
+/// a person can read it.
+pub fn read_or_art(art: &str) -> String {
+    read(art).unwrap_or_else(|| format!("\n{art}"))
+}
+
+// a glyph's columns from left to right
+fn columns(glyph: &[&str]) -> Vec<Vec<bool>> {
+    let width = glyph[0].len();
+    (0..width)
+        .map(|x| glyph.iter().map(|row| row.as_bytes()[x] == b'#').collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // draws the letters with the given spacing between them
+    fn draw<const H: usize>(font: &[(char, [&str; H])], text: &str, gap: usize) -> String {
+        (0..H)
+            .map(|y| {
+                text.chars()
+                    .map(|c| font.iter().find(|(l, _)| *l == c).unwrap().1[y])
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(gap))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let small: String = SMALL.iter().map(|(c, _)| c).collect();
+        assert_eq!(Some(small.clone()), read(&draw(&SMALL, &small, 1)));
+        let large: String = LARGE.iter().map(|(c, _)| c).collect();
+        assert_eq!(Some(large.clone()), read(&draw(&LARGE, &large, 2)));
+    }
+
+    #[test]
+    fn test_read() {
+        // 2022 day10, with a blank row above and ragged lines
+        let art = "
+####.####.####..##..#..#...##..##..###..
+#.......#.#....#..#.#..#....#.#..#.#..#.
+###....#..###..#....####....#.#..#.###..
+#.....#...#....#....#..#....#.####.#..#.
+#....#....#....#..#.#..#.#..#.#..#.#..#.
+####.####.#.....##..#..#..##..#..#.###";
+        assert_eq!(Some("EZFCHJAB".to_string()), read(art));
+        assert_eq!("EZFCHJAB", read_or_art(art));
+
+        let unknown = "#.#\n.#.\n#.#\n.#.\n#.#\n.#.";
+        assert_eq!(None, read(unknown));
+        assert_eq!(format!("\n{unknown}"), read_or_art(unknown));
+        assert_eq!(None, read("####\n#..#\n####"));
+        assert_eq!(None, read("...."));
+    }
+}
@@ -0,0 +1,90 @@
+// Exact polynomial extrapolation. Handy for puzzles where the answer grows polynomially with the
+// step count and we can only afford to simulate the first few steps (e.g. 2023 day09 and day21).
+
+use num::Zero;
+
+use crate::fraction::Fraction;
+
+/// Given the values of a polynomial at `x = 0, 1, ..., values.len() - 1`, evaluates it at `x = n`.
+/// The polynomial is the one of lowest degree that passes through every value.
+///
+/// Uses Newton's forward difference formula `f(n) = sum(C(n, j) * Δ^j f(0))`, which only needs
+/// integer arithmetic, so `n` can be huge (or negative) without losing precision.
+pub fn extrapolate(values: &[i128], n: i128) -> i128 {
+    let mut res = 0;
+    // C(n, 0)
+    let mut binomial = 1;
+    for (j, delta) in forward_differences(values).into_iter().enumerate() {
+        if j > 0 {
+            // C(n, j) = C(n, j - 1) * (n - j + 1) / j, the division is always exact
+            binomial = binomial * (n - j as i128 + 1) / j as i128;
+        }
+        res += binomial * delta;
+    }
+    res
+}
+
+/// The leading entry of each row of the difference table, i.e. `[f(0), Δf(0), Δ²f(0), ...]`.
+/// Trailing zero differences are dropped.
+pub fn forward_differences(values: &[i128]) -> Vec<i128> {
+    let mut row = values.to_vec();
+    let mut leading = Vec::new();
+    while !row.iter().all(|v| *v == 0) {
+        leading.push(row[0]);
+        row = row.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    leading
+}
+
+/// Evaluates the lowest degree polynomial passing through every `(x, y)` point at `x`, using
+/// Lagrange interpolation. Unlike `extrapolate` the points don't need to be evenly spaced, but the
+/// result may not be an integer.
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Fraction {
+    let mut res = Fraction::zero();
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = Fraction::from_integer(yi);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i != j {
+                assert!(xi != xj, "sample points must have distinct x values");
+                term = term * Fraction::new(x - xj, xi - xj);
+            }
+        }
+        res = res + term;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // 2023 day09 sample
+    #[rstest]
+    #[case(&[0, 3, 6, 9, 12, 15], 18, -3)]
+    #[case(&[1, 3, 6, 10, 15, 21], 28, 0)]
+    #[case(&[10, 13, 16, 21, 30, 45], 68, 5)]
+    fn test_extrapolate(#[case] values: &[i128], #[case] next: i128, #[case] prev: i128) {
+        assert_eq!(next, extrapolate(values, values.len() as i128));
+        assert_eq!(prev, extrapolate(values, -1));
+    }
+
+    #[test]
+    fn test_extrapolate_far() {
+        // f(x) = 3x^2 - 2x + 7
+        let f = |x: i128| 3 * x * x - 2 * x + 7;
+        let values = [f(0), f(1), f(2)];
+        assert_eq!(f(202300), extrapolate(&values, 202300));
+        assert_eq!(f(-1_000_000_000), extrapolate(&values, -1_000_000_000));
+        assert_eq!(0, extrapolate(&[], 10));
+    }
+
+    #[test]
+    fn test_lagrange() {
+        let f = |x: i128| x * x * x - 4 * x;
+        let points = [(-3, f(-3)), (1, f(1)), (4, f(4)), (10, f(10))];
+        assert_eq!(Fraction::from_integer(f(1000)), lagrange(&points, 1000));
+        // a line through (0, 0) and (2, 1) is at 1/2 when x = 1
+        assert_eq!(Fraction::new(1, 2), lagrange(&[(0, 0), (2, 1)], 1));
+    }
+}
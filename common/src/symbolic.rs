@@ -0,0 +1,215 @@
+// Just enough symbolic algebra to solve "which value makes both sides equal" puzzles (e.g. 2022
+// day21), where an expression tree has a single unknown leaf.
+
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+};
+
+use num::{One, Zero};
+
+use crate::fraction::Fraction;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(Fraction),
+    Var(String),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+}
+
+/// `coeff * x + constant` for some unknown `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub coeff: Fraction,
+    pub constant: Fraction,
+}
+
+impl Linear {
+    fn constant(constant: Fraction) -> Self {
+        Self {
+            coeff: Fraction::zero(),
+            constant,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.coeff.is_zero()
+    }
+
+    fn scale(self, by: Fraction) -> Self {
+        Self {
+            coeff: self.coeff * by,
+            constant: self.constant * by,
+        }
+    }
+}
+
+impl Expr {
+    pub fn num(n: i128) -> Expr {
+        Expr::Num(Fraction::from_integer(n))
+    }
+
+    pub fn var(name: impl Into<String>) -> Expr {
+        Expr::Var(name.into())
+    }
+
+    /// Folds every subtree that doesn't contain a variable into a single number. Divisions by zero
+    /// are left alone.
+    pub fn simplify(self) -> Expr {
+        let e = match self {
+            Expr::Num(_) | Expr::Var(_) => return self,
+            Expr::Add(l, r) => l.simplify() + r.simplify(),
+            Expr::Sub(l, r) => l.simplify() - r.simplify(),
+            Expr::Mul(l, r) => l.simplify() * r.simplify(),
+            Expr::Div(l, r) => l.simplify() / r.simplify(),
+        };
+        e.fold().map_or(e, Expr::Num)
+    }
+
+    // the value of an operation on two numbers
+    fn fold(&self) -> Option<Fraction> {
+        let (Expr::Add(l, r) | Expr::Sub(l, r) | Expr::Mul(l, r) | Expr::Div(l, r)) = self else {
+            return None;
+        };
+        let (Expr::Num(a), Expr::Num(b)) = (l.as_ref(), r.as_ref()) else {
+            return None;
+        };
+        match self {
+            Expr::Add(..) => Some(*a + *b),
+            Expr::Sub(..) => Some(*a - *b),
+            Expr::Mul(..) => Some(*a * *b),
+            Expr::Div(..) => (!b.is_zero()).then(|| *a / *b),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Rewrites the expression as `coeff * var + constant`. Fails if `var` is multiplied by
+    /// itself or divided into something (i.e. the expression isn't linear in `var`), if there is
+    /// another variable, or if something is divided by zero.
+    pub fn linear(&self, var: &str) -> anyhow::Result<Linear> {
+        Ok(match self {
+            Expr::Num(n) => Linear::constant(*n),
+            Expr::Var(v) if v == var => Linear {
+                coeff: Fraction::one(),
+                constant: Fraction::zero(),
+            },
+            Expr::Var(v) => anyhow::bail!("unexpected variable '{v}' while solving for '{var}'"),
+            Expr::Add(l, r) | Expr::Sub(l, r) => {
+                let (l, r) = (l.linear(var)?, r.linear(var)?);
+                let r = if matches!(self, Expr::Sub(..)) {
+                    r.scale(-Fraction::one())
+                } else {
+                    r
+                };
+                Linear {
+                    coeff: l.coeff + r.coeff,
+                    constant: l.constant + r.constant,
+                }
+            }
+            Expr::Mul(l, r) => {
+                let (l, r) = (l.linear(var)?, r.linear(var)?);
+                match (l.is_constant(), r.is_constant()) {
+                    (true, _) => r.scale(l.constant),
+                    (_, true) => l.scale(r.constant),
+                    _ => anyhow::bail!("'{var}' appears non-linearly in {self}"),
+                }
+            }
+            Expr::Div(l, r) => {
+                let (l, r) = (l.linear(var)?, r.linear(var)?);
+                anyhow::ensure!(r.is_constant(), "'{var}' appears non-linearly in {self}");
+                anyhow::ensure!(!r.constant.is_zero(), "division by zero in {self}");
+                l.scale(r.constant.recip())
+            }
+        })
+    }
+}
+
+/// Finds the value of `var` that makes `lhs == rhs`. Only linear equations are supported, so there
+/// is at most one answer and it is exact.
+pub fn solve(lhs: &Expr, rhs: &Expr, var: &str) -> anyhow::Result<Fraction> {
+    let (l, r) = (lhs.linear(var)?, rhs.linear(var)?);
+    // l.coeff * x + l.constant = r.coeff * x + r.constant
+    let coeff = l.coeff - r.coeff;
+    let constant = r.constant - l.constant;
+    if coeff.is_zero() {
+        if constant.is_zero() {
+            anyhow::bail!("every value of '{var}' is a solution");
+        }
+        anyhow::bail!("no value of '{var}' is a solution");
+    }
+    Ok(constant / coeff)
+}
+
+macro_rules! impl_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Expr {
+            type Output = Expr;
+
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::$trait(Box::new(self), Box::new(rhs))
+            }
+        }
+    };
+}
+
+impl_op!(Add, add);
+impl_op!(Sub, sub);
+impl_op!(Mul, mul);
+impl_op!(Div, div);
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Var(v) => write!(f, "{v}"),
+            Expr::Add(l, r) => write!(f, "({l} + {r})"),
+            Expr::Sub(l, r) => write!(f, "({l} - {r})"),
+            Expr::Mul(l, r) => write!(f, "({l} * {r})"),
+            Expr::Div(l, r) => write!(f, "({l} / {r})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Expr {
+        Expr::var("x")
+    }
+
+    #[test]
+    fn test_simplify() {
+        let e = (Expr::num(2) + Expr::num(3)) * x() - Expr::num(8) / Expr::num(4);
+        assert_eq!(Expr::num(5) * x() - Expr::num(2), e.simplify());
+        let e = Expr::num(1) / Expr::num(0);
+        assert_eq!(e.clone(), e.simplify());
+    }
+
+    #[test]
+    fn test_solve() {
+        // 2022 day21 sample: (4 + 2 * (x - 3)) / 4 == (32 - 2) * 5
+        let lhs = (Expr::num(4) + Expr::num(2) * (x() - Expr::num(3))) / Expr::num(4);
+        let rhs = (Expr::num(32) - Expr::num(2)) * Expr::num(5);
+        assert_eq!(Fraction::from_integer(301), solve(&lhs, &rhs, "x").unwrap());
+
+        // 10 / 4 - x == x / 3
+        let lhs = Expr::num(10) / Expr::num(4) - x();
+        let rhs = x() / Expr::num(3);
+        assert_eq!(Fraction::new(15, 8), solve(&lhs, &rhs, "x").unwrap());
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let square = x() * (x() + Expr::num(1));
+        assert!(solve(&square, &Expr::num(6), "x").is_err());
+        let recip = Expr::num(1) / x();
+        assert!(solve(&recip, &Expr::num(2), "x").is_err());
+        // x - x == 1
+        assert!(solve(&(x() - x()), &Expr::num(1), "x").is_err());
+        assert!(solve(&Expr::var("y"), &Expr::num(1), "x").is_err());
+    }
+}
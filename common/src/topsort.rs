@@ -0,0 +1,257 @@
+// Topological sorting with Kahn's algorithm. Nodes are interned to indices as they are added so
+// sorting is O(V + E), and ties are broken by insertion order (or a priority) so the output is
+// always the same for the same input.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use fxhash::FxHashMap;
+
+#[derive(Debug, Clone)]
+pub struct TopSort<T> {
+    ids: FxHashMap<T, usize>,
+    nodes: Vec<T>,
+    succ: Vec<Vec<usize>>,
+    num_prec: Vec<usize>,
+}
+
+impl<T> Default for TopSort<T> {
+    fn default() -> Self {
+        Self {
+            ids: Default::default(),
+            nodes: Default::default(),
+            succ: Default::default(),
+            num_prec: Default::default(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> TopSort<T> {
+    pub fn new() -> TopSort<T> {
+        Default::default()
+    }
+
+    /// Adds a node without any dependencies, unless it is already there.
+    pub fn add_node(&mut self, node: T) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.succ.push(Vec::new());
+        self.num_prec.push(0);
+        id
+    }
+
+    /// Registers a dependency between two vertices.
+    /// * `prec` The element appears before `succ`.
+    /// * `succ` The element appears after `prec`.
+    pub fn add_dependency(&mut self, prec: T, succ: T) {
+        let (prec, succ) = (self.add_node(prec), self.add_node(succ));
+        self.succ[prec].push(succ);
+        self.num_prec[succ] += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Every node, with nodes that become ready at the same time kept in the order they were
+    /// added.
+    pub fn sorted(self) -> anyhow::Result<Vec<T>> {
+        let order = self.kahn(&mut VecDeque::new())?;
+        Ok(order.into_iter().flatten().collect())
+    }
+
+    /// Every node, picking the ready node with the smallest key each time. With `|n| n.clone()`
+    /// this gives the lexicographically smallest ordering.
+    pub fn sorted_by_key<K: Ord, F: Fn(&T) -> K>(self, key: F) -> anyhow::Result<Vec<T>> {
+        // rank every node by its key up front so the heap only has to compare numbers
+        let mut by_rank: Vec<usize> = (0..self.len()).collect();
+        by_rank.sort_by_cached_key(|&id| key(&self.nodes[id]));
+        let mut rank = vec![0; self.len()];
+        for (r, &id) in by_rank.iter().enumerate() {
+            rank[id] = r;
+        }
+        let mut ready = Priority {
+            rank,
+            by_rank,
+            heap: BinaryHeap::new(),
+        };
+        let order = self.kahn(&mut ready)?;
+        Ok(order.into_iter().flatten().collect())
+    }
+
+    /// Groups the nodes into layers where everything in a layer only depends on earlier layers,
+    /// i.e. each layer is every node that is ready once the previous layers are done.
+    pub fn layers(self) -> anyhow::Result<Vec<Vec<T>>> {
+        self.kahn(&mut Vec::new())
+    }
+
+    // Kahn's algorithm, `ready` decides which of the nodes that are ready to go come out next.
+    // Fails if some nodes never become ready.
+    fn kahn(mut self, ready: &mut impl Ready) -> anyhow::Result<Vec<Vec<T>>> {
+        for id in 0..self.len() {
+            if self.num_prec[id] == 0 {
+                ready.push(id);
+            }
+        }
+
+        let mut done = 0;
+        let mut order = Vec::new();
+        while let Some(batch) = ready.pop() {
+            for &id in &batch {
+                for &s in &self.succ[id] {
+                    self.num_prec[s] -= 1;
+                    if self.num_prec[s] == 0 {
+                        ready.push(s);
+                    }
+                }
+            }
+            done += batch.len();
+            order.push(batch);
+        }
+
+        if done < self.len() {
+            let cycle: Vec<&T> = self
+                .find_cycle()
+                .into_iter()
+                .map(|id| &self.nodes[id])
+                .collect();
+            anyhow::bail!("dependency cycle between {cycle:?}");
+        }
+        Ok(order
+            .into_iter()
+            .map(|batch| batch.into_iter().map(|id| self.nodes[id].clone()).collect())
+            .collect())
+    }
+
+    // Called when sorting gets stuck. Every node that's left still has a predecessor that's left,
+    // so walking backwards through them has to go round a cycle eventually.
+    fn find_cycle(&self) -> Vec<usize> {
+        let left = |id: usize| self.num_prec[id] > 0;
+        let mut prec = vec![None; self.len()];
+        for (id, succ) in self.succ.iter().enumerate().filter(|(id, _)| left(*id)) {
+            for &s in succ.iter().filter(|s| left(**s)) {
+                prec[s] = Some(id);
+            }
+        }
+
+        let mut seen = vec![None; self.len()];
+        let mut node = (0..self.len()).find(|&id| left(id)).unwrap();
+        let mut path = Vec::new();
+        while seen[node].is_none() {
+            seen[node] = Some(path.len());
+            path.push(node);
+            node = prec[node].unwrap();
+        }
+        let mut cycle = path.split_off(seen[node].unwrap());
+        cycle.reverse();
+        cycle
+    }
+}
+
+// The nodes that are ready to be output.
+trait Ready {
+    fn push(&mut self, id: usize);
+    // the next nodes to output, together
+    fn pop(&mut self) -> Option<Vec<usize>>;
+}
+
+impl Ready for VecDeque<usize> {
+    fn push(&mut self, id: usize) {
+        self.push_back(id);
+    }
+
+    fn pop(&mut self) -> Option<Vec<usize>> {
+        self.pop_front().map(|id| vec![id])
+    }
+}
+
+// everything that is ready comes out as one layer
+impl Ready for Vec<usize> {
+    fn push(&mut self, id: usize) {
+        Vec::push(self, id);
+    }
+
+    fn pop(&mut self) -> Option<Vec<usize>> {
+        let layer = std::mem::take(self);
+        (!layer.is_empty()).then_some(layer)
+    }
+}
+
+// the ready node with the lowest rank comes out first
+struct Priority {
+    rank: Vec<usize>,
+    by_rank: Vec<usize>,
+    heap: BinaryHeap<Reverse<usize>>,
+}
+
+impl Ready for Priority {
+    fn push(&mut self, id: usize) {
+        self.heap.push(Reverse(self.rank[id]));
+    }
+
+    fn pop(&mut self) -> Option<Vec<usize>> {
+        self.heap.pop().map(|Reverse(r)| vec![self.by_rank[r]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TopSort<char> {
+        let mut ts = TopSort::new();
+        for (a, b) in [('c', 'a'), ('c', 'b'), ('a', 'd'), ('b', 'd'), ('e', 'd')] {
+            ts.add_dependency(a, b);
+        }
+        ts.add_node('f');
+        ts
+    }
+
+    #[test]
+    fn test_sorted() {
+        assert_eq!(
+            vec!['c', 'e', 'f', 'a', 'b', 'd'],
+            sample().sorted().unwrap()
+        );
+        assert_eq!(
+            vec!['c', 'a', 'b', 'e', 'd', 'f'],
+            sample().sorted_by_key(|c| *c).unwrap()
+        );
+        assert_eq!(
+            vec!['f', 'e', 'c', 'b', 'a', 'd'],
+            sample().sorted_by_key(|c| Reverse(*c)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_layers() {
+        assert_eq!(
+            vec![vec!['c', 'e', 'f'], vec!['a', 'b'], vec!['d']],
+            sample().layers().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut ts = sample();
+        // d -> x -> y -> a -> d
+        ts.add_dependency('d', 'x');
+        ts.add_dependency('x', 'y');
+        ts.add_dependency('y', 'a');
+        ts.add_dependency('y', 'z');
+        let err = ts.sorted().unwrap_err().to_string();
+        assert_eq!("dependency cycle between ['d', 'x', 'y', 'a']", err);
+    }
+}
@@ -0,0 +1,186 @@
+// Disjoint sets over any hashable key, for puzzles that merge things into clusters.
+
+use std::hash::Hash;
+
+use fxhash::FxHashMap;
+
+/// Union-find with path compression and union by size. Keys are added the first time they are
+/// seen, each in a set of its own.
+#[derive(Debug, Clone)]
+pub struct UnionFind<K> {
+    index: FxHashMap<K, usize>,
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl<K> Default for UnionFind<K> {
+    fn default() -> Self {
+        Self {
+            index: Default::default(),
+            parent: Default::default(),
+            size: Default::default(),
+            count: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for UnionFind<K> {
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        let mut uf = UnionFind::new();
+        for key in iter {
+            uf.insert(key);
+        }
+        uf
+    }
+}
+
+impl<K: Hash + Eq> UnionFind<K> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds `key` in a set of its own, unless it is already there.
+    pub fn insert(&mut self, key: K) -> usize {
+        let next = self.parent.len();
+        let idx = *self.index.entry(key).or_insert(next);
+        if idx == next {
+            self.parent.push(idx);
+            self.size.push(1);
+            self.count += 1;
+        }
+        idx
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// An id for the set containing `key`, equal for every key in the set. Ids change as sets are
+    /// merged.
+    pub fn find(&mut self, key: &K) -> Option<usize> {
+        let idx = *self.index.get(key)?;
+        Some(self.root(idx))
+    }
+
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        matches!((self.find(a), self.find(b)), (Some(a), Some(b)) if a == b)
+    }
+
+    /// Size of the set containing `key`, 0 if it has never been seen.
+    pub fn size_of(&mut self, key: &K) -> usize {
+        self.find(key).map_or(0, |root| self.size[root])
+    }
+
+    /// Merges the sets containing `a` and `b`, adding them first if needed. Returns false if they
+    /// were already in the same set.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        let (mut a, mut b) = (self.root(a), self.root(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    /// The size of every set, largest first.
+    pub fn set_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    fn root(&mut self, mut idx: usize) -> usize {
+        // path halving, every other node on the way up is pointed at its grandparent
+        while self.parent[idx] != idx {
+            self.parent[idx] = self.parent[self.parent[idx]];
+            idx = self.parent[idx];
+        }
+        idx
+    }
+}
+
+/// Kruskal's algorithm. Returns the edges of a minimum spanning forest over `nodes` in the order
+/// they were picked, i.e. by increasing weight, with ties kept in their original order. The
+/// last edge is the one that finally connected everything (if the graph is connected).
+pub fn kruskal<K, W, N, E>(nodes: N, edges: E) -> Vec<(K, K, W)>
+where
+    K: Hash + Eq + Clone,
+    W: Ord,
+    N: IntoIterator<Item = K>,
+    E: IntoIterator<Item = (K, K, W)>,
+{
+    let mut uf: UnionFind<K> = nodes.into_iter().collect();
+    let mut edges: Vec<_> = edges.into_iter().collect();
+    edges.sort_by(|a, b| a.2.cmp(&b.2));
+
+    let mut tree = Vec::new();
+    for (a, b, w) in edges {
+        if uf.count() == 1 {
+            break;
+        }
+        if uf.union(a.clone(), b.clone()) {
+            tree.push((a, b, w));
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut uf: UnionFind<&str> = ["a", "b", "c", "d", "e"].into_iter().collect();
+        assert_eq!(5, uf.count());
+        assert!(uf.union("a", "b"));
+        assert!(uf.union("c", "b"));
+        assert!(!uf.union("a", "c"));
+        assert!(uf.union("d", "f"));
+        assert_eq!(6, uf.len());
+        assert_eq!(3, uf.count());
+        assert!(uf.same(&"a", &"c"));
+        assert!(!uf.same(&"a", &"d"));
+        assert!(!uf.same(&"a", &"z"));
+        assert_eq!(3, uf.size_of(&"b"));
+        assert_eq!(0, uf.size_of(&"z"));
+        assert_eq!(vec![3, 2, 1], uf.set_sizes());
+    }
+
+    #[test]
+    fn test_kruskal() {
+        let edges = [
+            ('a', 'b', 4),
+            ('a', 'c', 1),
+            ('b', 'c', 2),
+            ('c', 'd', 5),
+            ('b', 'd', 3),
+            ('e', 'f', 1),
+        ];
+        let tree = kruskal("abcdef".chars(), edges);
+        assert_eq!(
+            vec![('a', 'c', 1), ('e', 'f', 1), ('b', 'c', 2), ('b', 'd', 3)],
+            tree
+        );
+    }
+}